@@ -66,6 +66,10 @@ pub trait ReadableDict {
     }
 
     /// Returns the bitflags that are set for the dict.
+    ///
+    /// The flags apply to the whole dict: `spa_dict_item` has no flags, so the entries of a dict
+    /// can not be told apart by flags.
+    /// See [`Flags`] for the meaning of each flag.
     fn flags(&self) -> Flags {
         Flags::from_bits_truncate(unsafe { (*self.get_dict_ptr()).flags })
     }
@@ -80,6 +84,56 @@ pub trait ReadableDict {
         self.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
     }
 
+    /// Get the value associated with the provided key, ignoring the ASCII case of the keys.
+    ///
+    /// This can be used to look up keys provided by users, whose case may not match the canonical keys.
+    /// If several keys of the dict match, the value of the first one is returned.
+    ///
+    /// If the dict does not contain the key or the value is non-utf8, `None` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use libspa::prelude::*;
+    /// use libspa::{StaticDict, static_dict};
+    ///
+    /// static DICT: StaticDict = static_dict! {
+    ///     "media.class" => "Audio/Sink"
+    /// };
+    ///
+    /// assert_eq!(DICT.get("Media.Class"), None);
+    /// assert_eq!(DICT.get_ignore_case("Media.Class"), Some("Audio/Sink"));
+    /// ```
+    fn get_ignore_case(&self, key: &str) -> Option<&str> {
+        self.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Get the value associated with the provided key, split into a list of whitespace separated items.
+    ///
+    /// This is useful for properties holding multiple values, such as `device.intended-roles`.
+    ///
+    /// If the dict does not contain the key or the value is non-utf8, `None` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use libspa::prelude::*;
+    /// use libspa::{StaticDict, static_dict};
+    ///
+    /// static DICT: StaticDict = static_dict! {
+    ///     "device.intended-roles" => "Music  Movie Game"
+    /// };
+    ///
+    /// assert_eq!(
+    ///     DICT.get_list("device.intended-roles"),
+    ///     Some(vec!["Music", "Movie", "Game"])
+    /// );
+    /// assert_eq!(DICT.get_list("device.name"), None);
+    /// ```
+    fn get_list(&self, key: &str) -> Option<Vec<&str>> {
+        self.get(key).map(|v| v.split_whitespace().collect())
+    }
+
     /// Get the value associated with the provided key and convert it to a given type.
     ///
     /// If the dict does not contain the key or the value is non-utf8, `None` is returned.
@@ -121,6 +175,65 @@ pub trait ReadableDict {
             })
     }
 
+    /// List the keys whose value differ between this dict and `other`.
+    ///
+    /// Each item contains the key, its value in this dict and its value in `other`,
+    /// the value being `None` if the key is not present in the dict.
+    /// Keys present in this dict are listed first, in order, followed by the keys only present in `other`.
+    ///
+    /// Only the key-value pairs that are valid utf-8 are compared.
+    ///
+    /// # Examples
+    /// ```
+    /// use libspa::prelude::*;
+    /// use libspa::{StaticDict, static_dict};
+    ///
+    /// static OLD: StaticDict = static_dict! {
+    ///     "node.name" => "sink",
+    ///     "media.role" => "Music",
+    ///     "node.latency" => "1024/48000"
+    /// };
+    /// static NEW: StaticDict = static_dict! {
+    ///     "node.name" => "sink",
+    ///     "node.latency" => "256/48000",
+    ///     "node.description" => "Sink"
+    /// };
+    ///
+    /// assert_eq!(
+    ///     OLD.diff(&NEW),
+    ///     vec![
+    ///         ("media.role", Some("Music"), None),
+    ///         ("node.latency", Some("1024/48000"), Some("256/48000")),
+    ///         ("node.description", None, Some("Sink")),
+    ///     ]
+    /// );
+    /// ```
+    fn diff<'a, D: ReadableDict>(
+        &'a self,
+        other: &'a D,
+    ) -> Vec<(&'a str, Option<&'a str>, Option<&'a str>)> {
+        let mut diff: Vec<_> = self
+            .iter()
+            .filter_map(|(key, value)| {
+                let other_value = other.get(key);
+                if other_value == Some(value) {
+                    None
+                } else {
+                    Some((key, Some(value), other_value))
+                }
+            })
+            .collect();
+
+        diff.extend(
+            other
+                .iter()
+                .filter(|(key, _)| self.get(key).is_none())
+                .map(|(key, value)| (key, None, Some(value))),
+        );
+
+        diff
+    }
+
     #[doc(hidden)]
     /// [`Debug`] implementation, should not be used directly by users.
     fn debug(&self, name: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -221,6 +334,29 @@ pub trait WritableDict {
     /// Insert the key-value pair, overwriting any old value.
     fn insert<T: Into<Vec<u8>>>(&mut self, key: T, value: T);
 
+    /// Insert the key-value pair, formatting the value using its [`Display`](`fmt::Display`) implementation.
+    ///
+    /// Any old value is overwritten.
+    fn insert_fmt<K: Into<Vec<u8>>, V: fmt::Display>(&mut self, key: K, value: V) {
+        self.insert(key.into(), value.to_string().into_bytes());
+    }
+
+    /// Insert the key-value pair only if the dict does not contain the key yet.
+    ///
+    /// This is useful to set default values without overwriting the ones provided by the user.
+    fn set_default<T: Into<Vec<u8>>>(&mut self, key: T, value: T)
+    where
+        Self: ReadableDict,
+    {
+        let key = key.into();
+        let exists = self
+            .iter_cstr()
+            .any(|(k, _)| k.to_bytes() == key.as_slice());
+        if !exists {
+            self.insert(key, value.into());
+        }
+    }
+
     /// Remove the key-value pair if it exists.
     fn remove<T: Into<Vec<u8>>>(&mut self, key: T);
 
@@ -262,10 +398,15 @@ impl fmt::Debug for ForeignDict {
 
 bitflags! {
     /// Dictionary flags
+    ///
+    /// These flags describe the dict as a whole, they are returned by [`ReadableDict::flags`].
     pub struct Flags: u32 {
         // These flags are redefinitions from
         // https://gitlab.freedesktop.org/pipewire/pipewire/-/blob/master/spa/include/spa/utils/dict.h
         /// Dictionary has been sorted.
+        ///
+        /// The items are sorted by key, so that `spa_dict_lookup()` can look up keys
+        /// with a binary search instead of going through all items.
         const SORTED = spa_sys::SPA_DICT_FLAG_SORTED;
     }
 }
@@ -522,6 +663,55 @@ mod tests {
         assert_eq!(Some("V0"), dict.get("K0"));
     }
 
+    #[test]
+    fn test_get_ignore_case() {
+        let dict = static_dict! {
+            "Key.Name" => "V0",
+            "key.name" => "V1"
+        };
+
+        assert_eq!(Some("V0"), dict.get_ignore_case("KEY.NAME"));
+        assert_eq!(Some("V0"), dict.get_ignore_case("key.name"));
+        assert_eq!(Some("V1"), dict.get("key.name"));
+        assert_eq!(None, dict.get_ignore_case("key"));
+    }
+
+    #[test]
+    fn test_get_list() {
+        let dict = static_dict! {
+            "K0" => "V0 V1\tV2",
+            "K1" => "",
+            "K2" => "V0"
+        };
+
+        assert_eq!(Some(vec!["V0", "V1", "V2"]), dict.get_list("K0"));
+        assert_eq!(Some(vec![]), dict.get_list("K1"));
+        assert_eq!(Some(vec!["V0"]), dict.get_list("K2"));
+        assert_eq!(None, dict.get_list("K3"));
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = static_dict! {
+            "K0" => "V0",
+            "K1" => "V1"
+        };
+        let new = static_dict! {
+            "K1" => "V2",
+            "K2" => "V0"
+        };
+
+        assert_eq!(
+            vec![
+                ("K0", Some("V0"), None),
+                ("K1", Some("V1"), Some("V2")),
+                ("K2", None, Some("V0"))
+            ],
+            old.diff(&new)
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
     #[test]
     fn test_debug() {
         let dict = static_dict! {
@@ -3,6 +3,8 @@
 
 //! SPA direction.
 
+use std::{fmt, str::FromStr};
+
 /// A port direction.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Direction {
@@ -42,6 +44,44 @@ impl Direction {
     }
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Input => "input",
+            Self::Output => "output",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseDirectionError;
+
+    /// Parse a direction from its string form.
+    ///
+    /// Both the short form used by the `port.direction` property (`"in"` and `"out"`)
+    /// and the long form used by the [`Display`](`fmt::Display`) implementation (`"input"` and `"output"`) are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "in" | "input" => Ok(Self::Input),
+            "out" | "output" => Ok(Self::Output),
+            _ => Err(ParseDirectionError(s.to_string())),
+        }
+    }
+}
+
+/// An error raised by [`Direction::from_str`] if the string is not a valid direction.
+#[derive(Debug, PartialEq)]
+pub struct ParseDirectionError(String);
+
+impl std::error::Error for ParseDirectionError {}
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a valid direction", self.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +121,22 @@ mod tests {
         assert_eq!(Direction::Output.reverse(), Direction::Input);
         assert_eq!(Direction::Input.reverse(), Direction::Output);
     }
+
+    #[test]
+    fn display() {
+        assert_eq!(Direction::Input.to_string(), "input");
+        assert_eq!(Direction::Output.to_string(), "output");
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("in".parse(), Ok(Direction::Input));
+        assert_eq!("input".parse(), Ok(Direction::Input));
+        assert_eq!("out".parse(), Ok(Direction::Output));
+        assert_eq!("output".parse(), Ok(Direction::Output));
+        assert_eq!(
+            "sideways".parse::<Direction>(),
+            Err(ParseDirectionError("sideways".to_string()))
+        );
+    }
 }
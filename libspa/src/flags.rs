@@ -7,6 +7,10 @@ bitflags! {
     pub struct IoFlags: u32 {
         /// There is data to read
         const IN = spa_sys::SPA_IO_IN;
+        /// There is urgent data to read, such as out-of-band TCP data
+        // SPA does not define this flag, but its values match the ones of epoll,
+        // to which the flags are passed directly by the default system implementation.
+        const PRI = libc::EPOLLPRI as u32;
         /// Writing is possible
         const OUT = spa_sys::SPA_IO_OUT;
         /// An error has occurred
@@ -14,6 +14,7 @@ mod direction;
 pub mod hook;
 pub mod interface;
 pub mod list;
+pub mod param;
 pub mod pod;
 pub mod utils;
 pub use direction::*;
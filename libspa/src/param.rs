@@ -0,0 +1,238 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Types used in params, such as the media type and subtype of a format.
+
+pub mod audio;
+mod format;
+pub mod video;
+
+pub use format::{Format, FormatError};
+
+/// Define an enum mapping to a SPA enumeration and implement [`SpaEnum`](`crate::utils::SpaEnum`) for it.
+macro_rules! spa_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:ident,)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[allow(clippy::upper_case_acronyms)]
+        pub enum $name {
+            $(
+                #[doc = concat!("`", stringify!($value), "`")]
+                $variant,
+            )*
+        }
+
+        impl $crate::utils::SpaEnum for $name {
+            #[allow(non_upper_case_globals)]
+            fn from_id(id: $crate::utils::Id) -> Option<Self> {
+                match id.0 {
+                    $(spa_sys::$value => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn to_id(&self) -> $crate::utils::Id {
+                $crate::utils::Id(match self {
+                    $(Self::$variant => spa_sys::$value,)*
+                })
+            }
+        }
+
+        impl $name {
+            /// Get the name of the variant, e.g. `"S16LE"` for [`AudioFormat::S16LE`](`crate::param::audio::AudioFormat::S16LE`).
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => stringify!($variant),)*
+                }
+            }
+        }
+    };
+}
+pub(crate) use spa_enum;
+
+spa_enum! {
+    /// The media type of a format.
+    MediaType {
+        Unknown => spa_media_type_SPA_MEDIA_TYPE_unknown,
+        Audio => spa_media_type_SPA_MEDIA_TYPE_audio,
+        Video => spa_media_type_SPA_MEDIA_TYPE_video,
+        Image => spa_media_type_SPA_MEDIA_TYPE_image,
+        Binary => spa_media_type_SPA_MEDIA_TYPE_binary,
+        Stream => spa_media_type_SPA_MEDIA_TYPE_stream,
+        Application => spa_media_type_SPA_MEDIA_TYPE_application,
+    }
+}
+
+spa_enum! {
+    /// The media subtype of a format.
+    MediaSubtype {
+        Unknown => spa_media_subtype_SPA_MEDIA_SUBTYPE_unknown,
+        Raw => spa_media_subtype_SPA_MEDIA_SUBTYPE_raw,
+        Dsp => spa_media_subtype_SPA_MEDIA_SUBTYPE_dsp,
+        Iec958 => spa_media_subtype_SPA_MEDIA_SUBTYPE_iec958,
+        Dsd => spa_media_subtype_SPA_MEDIA_SUBTYPE_dsd,
+        Mp3 => spa_media_subtype_SPA_MEDIA_SUBTYPE_mp3,
+        Aac => spa_media_subtype_SPA_MEDIA_SUBTYPE_aac,
+        Vorbis => spa_media_subtype_SPA_MEDIA_SUBTYPE_vorbis,
+        Wma => spa_media_subtype_SPA_MEDIA_SUBTYPE_wma,
+        Ra => spa_media_subtype_SPA_MEDIA_SUBTYPE_ra,
+        Sbc => spa_media_subtype_SPA_MEDIA_SUBTYPE_sbc,
+        Adpcm => spa_media_subtype_SPA_MEDIA_SUBTYPE_adpcm,
+        G723 => spa_media_subtype_SPA_MEDIA_SUBTYPE_g723,
+        G726 => spa_media_subtype_SPA_MEDIA_SUBTYPE_g726,
+        G729 => spa_media_subtype_SPA_MEDIA_SUBTYPE_g729,
+        Amr => spa_media_subtype_SPA_MEDIA_SUBTYPE_amr,
+        Gsm => spa_media_subtype_SPA_MEDIA_SUBTYPE_gsm,
+        H264 => spa_media_subtype_SPA_MEDIA_SUBTYPE_h264,
+        Mjpg => spa_media_subtype_SPA_MEDIA_SUBTYPE_mjpg,
+        Dv => spa_media_subtype_SPA_MEDIA_SUBTYPE_dv,
+        Mpegts => spa_media_subtype_SPA_MEDIA_SUBTYPE_mpegts,
+        H263 => spa_media_subtype_SPA_MEDIA_SUBTYPE_h263,
+        Mpeg1 => spa_media_subtype_SPA_MEDIA_SUBTYPE_mpeg1,
+        Mpeg2 => spa_media_subtype_SPA_MEDIA_SUBTYPE_mpeg2,
+        Mpeg4 => spa_media_subtype_SPA_MEDIA_SUBTYPE_mpeg4,
+        Xvid => spa_media_subtype_SPA_MEDIA_SUBTYPE_xvid,
+        Vc1 => spa_media_subtype_SPA_MEDIA_SUBTYPE_vc1,
+        Vp8 => spa_media_subtype_SPA_MEDIA_SUBTYPE_vp8,
+        Vp9 => spa_media_subtype_SPA_MEDIA_SUBTYPE_vp9,
+        Bayer => spa_media_subtype_SPA_MEDIA_SUBTYPE_bayer,
+        Jpeg => spa_media_subtype_SPA_MEDIA_SUBTYPE_jpeg,
+        Midi => spa_media_subtype_SPA_MEDIA_SUBTYPE_midi,
+        Control => spa_media_subtype_SPA_MEDIA_SUBTYPE_control,
+    }
+}
+
+/// Define a table mapping the short names of the keys of an object type to their ids.
+macro_rules! key_names {
+    ($(#[$meta:meta])* $name:ident { $($key:literal => $value:ident,)* }) => {
+        $(#[$meta])*
+        const $name: &[(&str, u32)] = &[$(($key, spa_sys::$value),)*];
+    };
+}
+
+key_names! {
+    /// Keys of `Props` objects.
+    PROPS_KEYS {
+        "device" => spa_prop_SPA_PROP_device,
+        "deviceName" => spa_prop_SPA_PROP_deviceName,
+        "deviceFd" => spa_prop_SPA_PROP_deviceFd,
+        "card" => spa_prop_SPA_PROP_card,
+        "cardName" => spa_prop_SPA_PROP_cardName,
+        "minLatency" => spa_prop_SPA_PROP_minLatency,
+        "maxLatency" => spa_prop_SPA_PROP_maxLatency,
+        "periods" => spa_prop_SPA_PROP_periods,
+        "periodSize" => spa_prop_SPA_PROP_periodSize,
+        "periodEvent" => spa_prop_SPA_PROP_periodEvent,
+        "live" => spa_prop_SPA_PROP_live,
+        "rate" => spa_prop_SPA_PROP_rate,
+        "quality" => spa_prop_SPA_PROP_quality,
+        "waveType" => spa_prop_SPA_PROP_waveType,
+        "frequency" => spa_prop_SPA_PROP_frequency,
+        "volume" => spa_prop_SPA_PROP_volume,
+        "mute" => spa_prop_SPA_PROP_mute,
+        "patternType" => spa_prop_SPA_PROP_patternType,
+        "ditherType" => spa_prop_SPA_PROP_ditherType,
+        "truncate" => spa_prop_SPA_PROP_truncate,
+        "channelVolumes" => spa_prop_SPA_PROP_channelVolumes,
+        "volumeBase" => spa_prop_SPA_PROP_volumeBase,
+        "volumeStep" => spa_prop_SPA_PROP_volumeStep,
+        "channelMap" => spa_prop_SPA_PROP_channelMap,
+        "monitorMute" => spa_prop_SPA_PROP_monitorMute,
+        "monitorVolumes" => spa_prop_SPA_PROP_monitorVolumes,
+        "latencyOffsetNsec" => spa_prop_SPA_PROP_latencyOffsetNsec,
+        "softMute" => spa_prop_SPA_PROP_softMute,
+        "softVolumes" => spa_prop_SPA_PROP_softVolumes,
+        "brightness" => spa_prop_SPA_PROP_brightness,
+        "contrast" => spa_prop_SPA_PROP_contrast,
+        "saturation" => spa_prop_SPA_PROP_saturation,
+        "hue" => spa_prop_SPA_PROP_hue,
+        "gamma" => spa_prop_SPA_PROP_gamma,
+        "exposure" => spa_prop_SPA_PROP_exposure,
+        "gain" => spa_prop_SPA_PROP_gain,
+        "sharpness" => spa_prop_SPA_PROP_sharpness,
+        "params" => spa_prop_SPA_PROP_params,
+    }
+}
+
+key_names! {
+    /// Keys of `Format` objects.
+    FORMAT_KEYS {
+        "mediaType" => spa_format_SPA_FORMAT_mediaType,
+        "mediaSubtype" => spa_format_SPA_FORMAT_mediaSubtype,
+        "audio.format" => spa_format_SPA_FORMAT_AUDIO_format,
+        "audio.flags" => spa_format_SPA_FORMAT_AUDIO_flags,
+        "audio.rate" => spa_format_SPA_FORMAT_AUDIO_rate,
+        "audio.channels" => spa_format_SPA_FORMAT_AUDIO_channels,
+        "audio.position" => spa_format_SPA_FORMAT_AUDIO_position,
+        "video.format" => spa_format_SPA_FORMAT_VIDEO_format,
+        "video.modifier" => spa_format_SPA_FORMAT_VIDEO_modifier,
+        "video.size" => spa_format_SPA_FORMAT_VIDEO_size,
+        "video.framerate" => spa_format_SPA_FORMAT_VIDEO_framerate,
+        "video.maxFramerate" => spa_format_SPA_FORMAT_VIDEO_maxFramerate,
+        "video.views" => spa_format_SPA_FORMAT_VIDEO_views,
+        "video.interlaceMode" => spa_format_SPA_FORMAT_VIDEO_interlaceMode,
+        "video.pixelAspectRatio" => spa_format_SPA_FORMAT_VIDEO_pixelAspectRatio,
+        "video.multiviewMode" => spa_format_SPA_FORMAT_VIDEO_multiviewMode,
+        "video.multiviewFlags" => spa_format_SPA_FORMAT_VIDEO_multiviewFlags,
+        "video.chromaSite" => spa_format_SPA_FORMAT_VIDEO_chromaSite,
+        "video.colorRange" => spa_format_SPA_FORMAT_VIDEO_colorRange,
+        "video.colorMatrix" => spa_format_SPA_FORMAT_VIDEO_colorMatrix,
+        "video.transferFunction" => spa_format_SPA_FORMAT_VIDEO_transferFunction,
+        "video.colorPrimaries" => spa_format_SPA_FORMAT_VIDEO_colorPrimaries,
+    }
+}
+
+key_names! {
+    /// Keys of `ParamBuffers` objects.
+    BUFFERS_KEYS {
+        "buffers" => spa_param_buffers_SPA_PARAM_BUFFERS_buffers,
+        "blocks" => spa_param_buffers_SPA_PARAM_BUFFERS_blocks,
+        "size" => spa_param_buffers_SPA_PARAM_BUFFERS_size,
+        "stride" => spa_param_buffers_SPA_PARAM_BUFFERS_stride,
+        "align" => spa_param_buffers_SPA_PARAM_BUFFERS_align,
+        "dataType" => spa_param_buffers_SPA_PARAM_BUFFERS_dataType,
+    }
+}
+
+#[allow(non_upper_case_globals)]
+fn key_names(object_type: u32) -> &'static [(&'static str, u32)] {
+    match object_type {
+        spa_sys::SPA_TYPE_OBJECT_Props => PROPS_KEYS,
+        spa_sys::SPA_TYPE_OBJECT_Format => FORMAT_KEYS,
+        spa_sys::SPA_TYPE_OBJECT_ParamBuffers => BUFFERS_KEYS,
+        _ => &[],
+    }
+}
+
+/// Get the id of the key of an object of type `object_type` from its short name, as used by SPA,
+/// e.g. `"volume"` for `SPA_PROP_volume` or `"audio.rate"` for `SPA_FORMAT_AUDIO_rate`.
+///
+/// Only the keys of `Props`, `Format` and `ParamBuffers` objects are known.
+/// Returns `None` for other object types and for unknown names.
+///
+/// # Examples
+/// ```rust
+/// use libspa::param::key_from_name;
+///
+/// assert_eq!(
+///     key_from_name(spa_sys::SPA_TYPE_OBJECT_Props, "volume"),
+///     Some(spa_sys::spa_prop_SPA_PROP_volume)
+/// );
+/// assert_eq!(key_from_name(spa_sys::SPA_TYPE_OBJECT_Props, "audio.rate"), None);
+/// ```
+pub fn key_from_name(object_type: u32, name: &str) -> Option<u32> {
+    key_names(object_type)
+        .iter()
+        .find(|(key_name, _)| *key_name == name)
+        .map(|(_, key)| *key)
+}
+
+/// Get the short name of the key `key` of an object of type `object_type`.
+///
+/// This is the inverse of [`key_from_name`].
+pub fn key_name(object_type: u32, key: u32) -> Option<&'static str> {
+    key_names(object_type)
+        .iter()
+        .find(|(_, id)| *id == key)
+        .map(|(name, _)| *name)
+}
@@ -0,0 +1,54 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Audio specific param types.
+
+use super::spa_enum;
+
+spa_enum! {
+    /// The sample format of raw audio.
+    ///
+    /// Variants ending with `P` are planar formats, the others are interleaved.
+    AudioFormat {
+        Unknown => spa_audio_format_SPA_AUDIO_FORMAT_UNKNOWN,
+        Encoded => spa_audio_format_SPA_AUDIO_FORMAT_ENCODED,
+        S8 => spa_audio_format_SPA_AUDIO_FORMAT_S8,
+        U8 => spa_audio_format_SPA_AUDIO_FORMAT_U8,
+        S16LE => spa_audio_format_SPA_AUDIO_FORMAT_S16_LE,
+        S16BE => spa_audio_format_SPA_AUDIO_FORMAT_S16_BE,
+        U16LE => spa_audio_format_SPA_AUDIO_FORMAT_U16_LE,
+        U16BE => spa_audio_format_SPA_AUDIO_FORMAT_U16_BE,
+        S24_32LE => spa_audio_format_SPA_AUDIO_FORMAT_S24_32_LE,
+        S24_32BE => spa_audio_format_SPA_AUDIO_FORMAT_S24_32_BE,
+        U24_32LE => spa_audio_format_SPA_AUDIO_FORMAT_U24_32_LE,
+        U24_32BE => spa_audio_format_SPA_AUDIO_FORMAT_U24_32_BE,
+        S32LE => spa_audio_format_SPA_AUDIO_FORMAT_S32_LE,
+        S32BE => spa_audio_format_SPA_AUDIO_FORMAT_S32_BE,
+        U32LE => spa_audio_format_SPA_AUDIO_FORMAT_U32_LE,
+        U32BE => spa_audio_format_SPA_AUDIO_FORMAT_U32_BE,
+        S24LE => spa_audio_format_SPA_AUDIO_FORMAT_S24_LE,
+        S24BE => spa_audio_format_SPA_AUDIO_FORMAT_S24_BE,
+        U24LE => spa_audio_format_SPA_AUDIO_FORMAT_U24_LE,
+        U24BE => spa_audio_format_SPA_AUDIO_FORMAT_U24_BE,
+        S20LE => spa_audio_format_SPA_AUDIO_FORMAT_S20_LE,
+        S20BE => spa_audio_format_SPA_AUDIO_FORMAT_S20_BE,
+        U20LE => spa_audio_format_SPA_AUDIO_FORMAT_U20_LE,
+        U20BE => spa_audio_format_SPA_AUDIO_FORMAT_U20_BE,
+        S18LE => spa_audio_format_SPA_AUDIO_FORMAT_S18_LE,
+        S18BE => spa_audio_format_SPA_AUDIO_FORMAT_S18_BE,
+        U18LE => spa_audio_format_SPA_AUDIO_FORMAT_U18_LE,
+        U18BE => spa_audio_format_SPA_AUDIO_FORMAT_U18_BE,
+        F32LE => spa_audio_format_SPA_AUDIO_FORMAT_F32_LE,
+        F32BE => spa_audio_format_SPA_AUDIO_FORMAT_F32_BE,
+        F64LE => spa_audio_format_SPA_AUDIO_FORMAT_F64_LE,
+        F64BE => spa_audio_format_SPA_AUDIO_FORMAT_F64_BE,
+        U8P => spa_audio_format_SPA_AUDIO_FORMAT_U8P,
+        S16P => spa_audio_format_SPA_AUDIO_FORMAT_S16P,
+        S24_32P => spa_audio_format_SPA_AUDIO_FORMAT_S24_32P,
+        S32P => spa_audio_format_SPA_AUDIO_FORMAT_S32P,
+        S24P => spa_audio_format_SPA_AUDIO_FORMAT_S24P,
+        F32P => spa_audio_format_SPA_AUDIO_FORMAT_F32P,
+        F64P => spa_audio_format_SPA_AUDIO_FORMAT_F64P,
+        S8P => spa_audio_format_SPA_AUDIO_FORMAT_S8P,
+    }
+}
@@ -0,0 +1,349 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::{convert::TryFrom, fmt};
+
+use super::{audio::AudioFormat, MediaSubtype, MediaType};
+use crate::{
+    pod::{ChoiceValue, Object, Property, PropertyFlags, Value, ValueArray},
+    utils::{Choice, ChoiceEnum, ChoiceFlags, Id, SpaEnum},
+};
+
+/// A strongly-typed audio `Format` object.
+///
+/// This covers the `SPA_FORMAT_*` keys used by audio formats, so that the format of an audio stream
+/// can be built and inspected without handling the properties of the [`Object`] by hand.
+///
+/// Properties that are not set are left out of the object.
+///
+/// # Examples
+/// ```rust
+/// use libspa::{
+///     param::{audio::AudioFormat, Format, MediaSubtype, MediaType},
+///     pod::Object,
+///     utils::{Choice, ChoiceEnum, ChoiceFlags},
+/// };
+/// use std::convert::TryFrom;
+///
+/// let format = Format {
+///     format: Some(AudioFormat::F32LE),
+///     rate: Some(Choice(
+///         ChoiceFlags::empty(),
+///         ChoiceEnum::Range {
+///             default: 48000,
+///             min: 44100,
+///             max: 96000,
+///         },
+///     )),
+///     channels: Some(2),
+///     ..Format::new(MediaType::Audio, MediaSubtype::Raw)
+/// };
+///
+/// let object: Object = format.clone().into();
+/// assert_eq!(Format::try_from(&object), Ok(format));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Format {
+    /// The media type, `SPA_FORMAT_mediaType`.
+    pub media_type: MediaType,
+    /// The media subtype, `SPA_FORMAT_mediaSubtype`.
+    pub media_subtype: MediaSubtype,
+    /// The sample format, `SPA_FORMAT_AUDIO_format`.
+    pub format: Option<AudioFormat>,
+    /// The audio flags, `SPA_FORMAT_AUDIO_flags`.
+    pub flags: Option<i32>,
+    /// The sample rate, `SPA_FORMAT_AUDIO_rate`.
+    pub rate: Option<Choice<i32>>,
+    /// The number of channels, `SPA_FORMAT_AUDIO_channels`.
+    pub channels: Option<i32>,
+    /// The position of each channel, `SPA_FORMAT_AUDIO_position`, as `SPA_AUDIO_CHANNEL_*` ids.
+    pub position: Option<Vec<Id>>,
+}
+
+impl Format {
+    /// Create a new format of the given type and subtype, with all other properties unset.
+    pub fn new(media_type: MediaType, media_subtype: MediaSubtype) -> Self {
+        Self {
+            media_type,
+            media_subtype,
+            format: None,
+            flags: None,
+            rate: None,
+            channels: None,
+            position: None,
+        }
+    }
+}
+
+/// Error returned when converting an [`Object`] into a [`Format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatError {
+    /// The object is not of type `SPA_TYPE_OBJECT_Format`.
+    ///
+    /// Contains the type of the object.
+    WrongObjectType(u32),
+    /// A required property is missing.
+    ///
+    /// Contains the key of the property.
+    PropertyMissing(u32),
+    /// The value of a property does not have the expected type or is not known.
+    ///
+    /// Contains the key of the property.
+    InvalidProperty(u32),
+}
+
+impl std::error::Error for FormatError {}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::WrongObjectType(type_) => {
+                write!(f, "object of type {} is not a format", type_)
+            }
+            FormatError::PropertyMissing(key) => write!(f, "format property {} is missing", key),
+            FormatError::InvalidProperty(key) => write!(f, "format property {} is invalid", key),
+        }
+    }
+}
+
+/// Get the value of an `Id` property, which may be wrapped in a choice with a single value.
+fn fixed_id(value: &Value) -> Option<Id> {
+    match value {
+        Value::Id(id) => Some(*id),
+        Value::Choice(ChoiceValue::Id(Choice(_, ChoiceEnum::None(id)))) => Some(*id),
+        _ => None,
+    }
+}
+
+/// Get the value of an `Int` property, which may be wrapped in a choice with a single value.
+fn fixed_int(value: &Value) -> Option<i32> {
+    match value {
+        Value::Int(value) => Some(*value),
+        Value::Choice(ChoiceValue::Int(Choice(_, ChoiceEnum::None(value)))) => Some(*value),
+        _ => None,
+    }
+}
+
+impl TryFrom<&Object> for Format {
+    type Error = FormatError;
+
+    fn try_from(object: &Object) -> Result<Self, Self::Error> {
+        if object.type_ != spa_sys::SPA_TYPE_OBJECT_Format {
+            return Err(FormatError::WrongObjectType(object.type_));
+        }
+
+        /// Convert the optional property `key` using `convert`.
+        fn optional<T>(
+            object: &Object,
+            key: u32,
+            convert: impl FnOnce(&Value) -> Option<T>,
+        ) -> Result<Option<T>, FormatError> {
+            object
+                .get(key)
+                .map(|value| convert(value).ok_or(FormatError::InvalidProperty(key)))
+                .transpose()
+        }
+
+        fn enum_value<T: SpaEnum>(value: &Value) -> Option<T> {
+            fixed_id(value).and_then(T::from_id)
+        }
+
+        let media_type = optional(object, spa_sys::spa_format_SPA_FORMAT_mediaType, enum_value)?
+            .ok_or(FormatError::PropertyMissing(
+                spa_sys::spa_format_SPA_FORMAT_mediaType,
+            ))?;
+        let media_subtype = optional(
+            object,
+            spa_sys::spa_format_SPA_FORMAT_mediaSubtype,
+            enum_value,
+        )?
+        .ok_or(FormatError::PropertyMissing(
+            spa_sys::spa_format_SPA_FORMAT_mediaSubtype,
+        ))?;
+
+        Ok(Self {
+            media_type,
+            media_subtype,
+            format: optional(
+                object,
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_format,
+                enum_value,
+            )?,
+            flags: optional(
+                object,
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_flags,
+                fixed_int,
+            )?,
+            rate: optional(
+                object,
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_rate,
+                |value| match value {
+                    Value::Int(rate) => Some(Choice(ChoiceFlags::empty(), ChoiceEnum::None(*rate))),
+                    Value::Choice(ChoiceValue::Int(rate)) => Some(rate.clone()),
+                    _ => None,
+                },
+            )?,
+            channels: optional(
+                object,
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_channels,
+                fixed_int,
+            )?,
+            position: optional(
+                object,
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_position,
+                |value| match value {
+                    Value::ValueArray(ValueArray::Id(position)) => Some(position.clone()),
+                    _ => None,
+                },
+            )?,
+        })
+    }
+}
+
+impl TryFrom<Object> for Format {
+    type Error = FormatError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        Self::try_from(&object)
+    }
+}
+
+/// The object is created with the `SPA_PARAM_EnumFormat` id,
+/// set [`Object::id`] to `SPA_PARAM_Format` if needed.
+impl From<Format> for Object {
+    fn from(format: Format) -> Self {
+        fn property(key: u32, value: Value) -> Property {
+            Property {
+                key,
+                flags: PropertyFlags::empty(),
+                value,
+            }
+        }
+
+        let mut properties = vec![
+            property(
+                spa_sys::spa_format_SPA_FORMAT_mediaType,
+                Value::Id(format.media_type.to_id()),
+            ),
+            property(
+                spa_sys::spa_format_SPA_FORMAT_mediaSubtype,
+                Value::Id(format.media_subtype.to_id()),
+            ),
+        ];
+
+        if let Some(audio_format) = format.format {
+            properties.push(property(
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_format,
+                Value::Id(audio_format.to_id()),
+            ));
+        }
+        if let Some(flags) = format.flags {
+            properties.push(property(
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_flags,
+                Value::Int(flags),
+            ));
+        }
+        if let Some(rate) = format.rate {
+            properties.push(property(
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_rate,
+                Value::Choice(ChoiceValue::Int(rate)),
+            ));
+        }
+        if let Some(channels) = format.channels {
+            properties.push(property(
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_channels,
+                Value::Int(channels),
+            ));
+        }
+        if let Some(position) = format.position {
+            properties.push(property(
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_position,
+                Value::ValueArray(ValueArray::Id(position)),
+            ));
+        }
+
+        Object {
+            type_: spa_sys::SPA_TYPE_OBJECT_Format,
+            id: spa_sys::spa_param_type_SPA_PARAM_EnumFormat,
+            properties,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_object() {
+        let object = Object {
+            type_: spa_sys::SPA_TYPE_OBJECT_Format,
+            id: spa_sys::spa_param_type_SPA_PARAM_Format,
+            properties: vec![
+                Property {
+                    key: spa_sys::spa_format_SPA_FORMAT_mediaType,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Id(MediaType::Audio.to_id()),
+                },
+                Property {
+                    key: spa_sys::spa_format_SPA_FORMAT_mediaSubtype,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Id(MediaSubtype::Raw.to_id()),
+                },
+                Property {
+                    key: spa_sys::spa_format_SPA_FORMAT_AUDIO_format,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Choice(ChoiceValue::Id(Choice(
+                        ChoiceFlags::empty(),
+                        ChoiceEnum::None(AudioFormat::S16LE.to_id()),
+                    ))),
+                },
+                Property {
+                    key: spa_sys::spa_format_SPA_FORMAT_AUDIO_rate,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Int(44100),
+                },
+            ],
+        };
+
+        let format = Format::try_from(&object).unwrap();
+        assert_eq!(
+            format,
+            Format {
+                format: Some(AudioFormat::S16LE),
+                rate: Some(Choice(ChoiceFlags::empty(), ChoiceEnum::None(44100))),
+                ..Format::new(MediaType::Audio, MediaSubtype::Raw)
+            }
+        );
+    }
+
+    #[test]
+    fn from_object_errors() {
+        let mut object: Object = Format::new(MediaType::Audio, MediaSubtype::Raw).into();
+        object.properties.push(Property {
+            key: spa_sys::spa_format_SPA_FORMAT_AUDIO_channels,
+            flags: PropertyFlags::empty(),
+            value: Value::String("stereo".to_string()),
+        });
+        assert_eq!(
+            Format::try_from(&object),
+            Err(FormatError::InvalidProperty(
+                spa_sys::spa_format_SPA_FORMAT_AUDIO_channels
+            ))
+        );
+
+        object.properties.remove(0);
+        assert_eq!(
+            Format::try_from(&object),
+            Err(FormatError::PropertyMissing(
+                spa_sys::spa_format_SPA_FORMAT_mediaType
+            ))
+        );
+
+        object.type_ = spa_sys::SPA_TYPE_OBJECT_Props;
+        assert_eq!(
+            Format::try_from(&object),
+            Err(FormatError::WrongObjectType(spa_sys::SPA_TYPE_OBJECT_Props))
+        );
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Video specific param types.
+
+use super::spa_enum;
+
+spa_enum! {
+    /// The pixel format of raw video.
+    VideoFormat {
+        Unknown => spa_video_format_SPA_VIDEO_FORMAT_UNKNOWN,
+        Encoded => spa_video_format_SPA_VIDEO_FORMAT_ENCODED,
+        I420 => spa_video_format_SPA_VIDEO_FORMAT_I420,
+        YV12 => spa_video_format_SPA_VIDEO_FORMAT_YV12,
+        YUY2 => spa_video_format_SPA_VIDEO_FORMAT_YUY2,
+        UYVY => spa_video_format_SPA_VIDEO_FORMAT_UYVY,
+        AYUV => spa_video_format_SPA_VIDEO_FORMAT_AYUV,
+        RGBx => spa_video_format_SPA_VIDEO_FORMAT_RGBx,
+        BGRx => spa_video_format_SPA_VIDEO_FORMAT_BGRx,
+        XRGB => spa_video_format_SPA_VIDEO_FORMAT_xRGB,
+        XBGR => spa_video_format_SPA_VIDEO_FORMAT_xBGR,
+        RGBA => spa_video_format_SPA_VIDEO_FORMAT_RGBA,
+        BGRA => spa_video_format_SPA_VIDEO_FORMAT_BGRA,
+        ARGB => spa_video_format_SPA_VIDEO_FORMAT_ARGB,
+        ABGR => spa_video_format_SPA_VIDEO_FORMAT_ABGR,
+        RGB => spa_video_format_SPA_VIDEO_FORMAT_RGB,
+        BGR => spa_video_format_SPA_VIDEO_FORMAT_BGR,
+        Y41B => spa_video_format_SPA_VIDEO_FORMAT_Y41B,
+        Y42B => spa_video_format_SPA_VIDEO_FORMAT_Y42B,
+        YVYU => spa_video_format_SPA_VIDEO_FORMAT_YVYU,
+        Y444 => spa_video_format_SPA_VIDEO_FORMAT_Y444,
+        NV12 => spa_video_format_SPA_VIDEO_FORMAT_NV12,
+        NV21 => spa_video_format_SPA_VIDEO_FORMAT_NV21,
+        GRAY8 => spa_video_format_SPA_VIDEO_FORMAT_GRAY8,
+        GRAY16BE => spa_video_format_SPA_VIDEO_FORMAT_GRAY16_BE,
+        GRAY16LE => spa_video_format_SPA_VIDEO_FORMAT_GRAY16_LE,
+        RGB16 => spa_video_format_SPA_VIDEO_FORMAT_RGB16,
+        BGR16 => spa_video_format_SPA_VIDEO_FORMAT_BGR16,
+        RGB15 => spa_video_format_SPA_VIDEO_FORMAT_RGB15,
+        BGR15 => spa_video_format_SPA_VIDEO_FORMAT_BGR15,
+        NV16 => spa_video_format_SPA_VIDEO_FORMAT_NV16,
+        NV24 => spa_video_format_SPA_VIDEO_FORMAT_NV24,
+        NV61 => spa_video_format_SPA_VIDEO_FORMAT_NV61,
+        VYUY => spa_video_format_SPA_VIDEO_FORMAT_VYUY,
+    }
+}
@@ -8,11 +8,15 @@
 //! but is much more specialized to fit the SPA pod format.
 
 pub mod deserialize;
+#[cfg(feature = "serde_json")]
+mod json;
 pub mod serialize;
 
 use std::{
+    convert::TryFrom,
     ffi::c_void,
-    io::{Seek, Write},
+    io::{Cursor, Seek, Write},
+    time::Duration,
 };
 
 use bitflags::bitflags;
@@ -34,7 +38,7 @@ use nom::{
 use deserialize::{BoolVisitor, NoneVisitor, PodDeserialize, PodDeserializer};
 use serialize::{PodSerialize, PodSerializer};
 
-use crate::utils::{Choice, Fd, Fraction, Id, Rectangle};
+use crate::utils::{Choice, ChoiceEnum, Fd, Fraction, Id, Rectangle};
 
 use self::deserialize::{
     ChoiceDoubleVisitor, ChoiceFdVisitor, ChoiceFloatVisitor, ChoiceFractionVisitor,
@@ -53,6 +57,26 @@ use self::deserialize::{
 ///
 /// If you want to have your type convert from and to a fixed sized pod, implement [`FixedSizedPod`] instead and choose
 /// a fitting implementor of this trait as the `CanonicalType` instead.
+///
+/// The implementors and the pod types they represent are:
+///
+/// | Rust type     | Pod type    |
+/// |---------------|-------------|
+/// | `()`          | `None`      |
+/// | `bool`        | `Bool`      |
+/// | [`Id`]        | `Id`        |
+/// | `i32`         | `Int`       |
+/// | `i64`         | `Long`      |
+/// | `f32`         | `Float`     |
+/// | `f64`         | `Double`    |
+/// | [`Rectangle`] | `Rectangle` |
+/// | [`Fraction`]  | `Fraction`  |
+/// | [`Fd`]        | `Fd`        |
+///
+/// Note that there are no pod types for unsigned integers, so `u32` and `u64` can not be serialized directly.
+/// Unsigned values in SPA pods usually are enumerated values, such as media types or formats,
+/// which have to be wrapped in [`Id`] to be serialized into an `Id` type pod.
+/// Numeric values have to be converted into `i32` or `i64` instead.
 pub trait CanonicalFixedSizedPod: private::CanonicalFixedSizedPodSeal {
     /// The raw type this serializes into.
     #[doc(hidden)]
@@ -327,6 +351,22 @@ impl<T: FixedSizedPod> PodSerialize for T {
     }
 }
 
+/// Serialize a [`Duration`] into a `Long` type pod holding its number of nanoseconds.
+///
+/// Durations too long to be represented are saturated to `i64::MAX` nanoseconds,
+/// while negative amounts of nanoseconds are converted to a zero duration.
+impl FixedSizedPod for Duration {
+    type CanonicalType = i64;
+
+    fn as_canonical_type(&self) -> Self::CanonicalType {
+        i64::try_from(self.as_nanos()).unwrap_or(i64::MAX)
+    }
+
+    fn from_canonical_type(canonical: &Self::CanonicalType) -> Self {
+        Duration::from_nanos(u64::try_from(*canonical).unwrap_or(0))
+    }
+}
+
 impl<'de> PodDeserialize<'de> for () {
     fn deserialize(
         deserializer: PodDeserializer<'de>,
@@ -383,6 +423,22 @@ impl<'de> PodDeserialize<'de> for i64 {
     }
 }
 
+impl<'de> PodDeserialize<'de> for Duration {
+    fn deserialize(
+        deserializer: PodDeserializer<'de>,
+    ) -> Result<
+        (Self, deserialize::DeserializeSuccess<'de>),
+        deserialize::DeserializeError<&'de [u8]>,
+    >
+    where
+        Self: Sized,
+    {
+        deserializer
+            .deserialize_long(LongVisitor)
+            .map(|(nsec, success)| (Duration::from_canonical_type(&nsec), success))
+    }
+}
+
 impl<'de> PodDeserialize<'de> for f32 {
     fn deserialize(
         deserializer: PodDeserializer<'de>,
@@ -627,6 +683,12 @@ pub enum Value {
     /// a string.
     String(String),
     /// a byte array.
+    ///
+    /// As a [`Value`] owns all of its data, the bytes are always copied out of the pod,
+    /// including by [`PodDeserializer::deserialize_any_from`](`deserialize::PodDeserializer::deserialize_any_from`),
+    /// and there is no borrowing variant of [`Value`].
+    /// To avoid copying large blobs, deserialize the pod into a `&[u8]` or a [`Cow<[u8]>`](`std::borrow::Cow`)
+    /// instead, which borrow the input.
     Bytes(Vec<u8>),
     /// a rectangle with width and height.
     Rectangle(Rectangle),
@@ -644,6 +706,230 @@ pub enum Value {
     Choice(ChoiceValue),
     /// a pointer.
     Pointer(u32, *const c_void),
+    /// a pod of a type that is not supported, such as a type added by a newer version of SPA.
+    ///
+    /// This is only produced by [`PodDeserializer::deserialize_any_from_keep_unknown`],
+    /// and serialized back as is.
+    Unknown {
+        /// the type of the pod.
+        type_: u32,
+        /// the body of the pod, without padding.
+        body: Vec<u8>,
+    },
+}
+
+impl Value {
+    /// Get the number of bytes this value occupies when serialized, including headers and padding.
+    ///
+    /// This can be used to allocate a buffer of the right size before serializing the value.
+    pub fn serialized_size(&self) -> usize {
+        /// Size of a pod with a body of size `body` once padded.
+        fn pod(body: usize) -> usize {
+            8 + align_up(body)
+        }
+
+        /// Size of a choice pod holding `choice`.
+        fn choice<T: CanonicalFixedSizedPod>(choice: &Choice<T>) -> usize {
+            let n_values = match &choice.1 {
+                ChoiceEnum::None(_) => 1,
+                ChoiceEnum::Range { .. } => 3,
+                ChoiceEnum::Step { .. } => 4,
+                ChoiceEnum::Enum { alternatives, .. } => 1 + alternatives.len(),
+                ChoiceEnum::Flags { flags, .. } => 1 + flags.len(),
+            };
+            pod(16 + n_values * T::SIZE as usize)
+        }
+
+        /// Size of an array pod holding `arr`.
+        fn array<T: CanonicalFixedSizedPod>(arr: &[T]) -> usize {
+            pod(8 + arr.len() * T::SIZE as usize)
+        }
+
+        match self {
+            Value::None => pod(<()>::SIZE as usize),
+            Value::Bool(_) => pod(bool::SIZE as usize),
+            Value::Id(_) => pod(Id::SIZE as usize),
+            Value::Int(_) => pod(i32::SIZE as usize),
+            Value::Long(_) => pod(i64::SIZE as usize),
+            Value::Float(_) => pod(f32::SIZE as usize),
+            Value::Double(_) => pod(f64::SIZE as usize),
+            // Strings are serialized with their nul terminator.
+            Value::String(s) => pod(s.len() + 1),
+            Value::Bytes(b) => pod(b.len()),
+            Value::Rectangle(_) => pod(Rectangle::SIZE as usize),
+            Value::Fraction(_) => pod(Fraction::SIZE as usize),
+            Value::Fd(_) => pod(Fd::SIZE as usize),
+            Value::ValueArray(arr) => match arr {
+                ValueArray::None(arr) => array(arr),
+                ValueArray::Bool(arr) => array(arr),
+                ValueArray::Id(arr) => array(arr),
+                ValueArray::Int(arr) => array(arr),
+                ValueArray::Long(arr) => array(arr),
+                ValueArray::Float(arr) => array(arr),
+                ValueArray::Double(arr) => array(arr),
+                ValueArray::Rectangle(arr) => array(arr),
+                ValueArray::Fraction(arr) => array(arr),
+                ValueArray::Fd(arr) => array(arr),
+            },
+            Value::Struct(fields) => pod(fields.iter().map(Value::serialized_size).sum()),
+            Value::Object(object) => pod(
+                // object type and id, followed by the key and flags and value of each property
+                8 + object
+                    .properties
+                    .iter()
+                    .map(|prop| 8 + prop.value.serialized_size())
+                    .sum::<usize>(),
+            ),
+            Value::Choice(choice_value) => match choice_value {
+                ChoiceValue::Int(c) => choice(c),
+                ChoiceValue::Long(c) => choice(c),
+                ChoiceValue::Float(c) => choice(c),
+                ChoiceValue::Double(c) => choice(c),
+                ChoiceValue::Id(c) => choice(c),
+                ChoiceValue::Rectangle(c) => choice(c),
+                ChoiceValue::Fraction(c) => choice(c),
+                ChoiceValue::Fd(c) => choice(c),
+            },
+            // pointer type and padding, followed by the pointer itself
+            Value::Pointer(_, _) => pod(8 + std::mem::size_of::<usize>()),
+            Value::Unknown { body, .. } => pod(body.len()),
+        }
+    }
+
+    /// Get the number of elements of an array, the number of fields of a struct,
+    /// or the number of properties of an object.
+    ///
+    /// Returns `None` for all other values.
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Value::ValueArray(arr) => Some(arr.len()),
+            Value::Struct(fields) => Some(fields.len()),
+            Value::Object(object) => Some(object.properties.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the array, struct or object has no element, field or property.
+    ///
+    /// Returns `None` for all other values, see [`len`](`Self::len`).
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Collapse a `Choice` of type `None` into the value it holds.
+    ///
+    /// Such a choice has a single possible value, for example in a format that has been fixated.
+    /// Other values, including choices offering multiple values, are returned unchanged.
+    ///
+    /// See [`ChoiceValue::fixed`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libspa::{
+    ///     pod::{ChoiceValue, Value},
+    ///     utils::{Choice, ChoiceEnum, ChoiceFlags},
+    /// };
+    ///
+    /// let choice = Value::Choice(ChoiceValue::Int(Choice(
+    ///     ChoiceFlags::empty(),
+    ///     ChoiceEnum::None(48000),
+    /// )));
+    /// assert_eq!(choice.unwrap_choice(), Value::Int(48000));
+    /// assert_eq!(Value::Int(48000).unwrap_choice(), Value::Int(48000));
+    /// ```
+    pub fn unwrap_choice(self) -> Value {
+        match self {
+            Value::Choice(ref choice) => choice.fixed().unwrap_or(self),
+            value => value,
+        }
+    }
+
+    /// Compare two values, considering a `Choice` of type `None` equal to the value it holds.
+    ///
+    /// This is useful to compare a format with the fixated format sent back by the server,
+    /// which may wrap its values into such choices.
+    /// The fields of structs and the properties of objects are compared the same way,
+    /// all other values must be equal.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libspa::{
+    ///     pod::{ChoiceValue, Value},
+    ///     utils::{Choice, ChoiceEnum, ChoiceFlags},
+    /// };
+    ///
+    /// let choice = Value::Choice(ChoiceValue::Int(Choice(
+    ///     ChoiceFlags::empty(),
+    ///     ChoiceEnum::None(48000),
+    /// )));
+    /// assert_ne!(choice, Value::Int(48000));
+    /// assert!(choice.semantic_eq(&Value::Int(48000)));
+    /// assert!(!choice.semantic_eq(&Value::Int(44100)));
+    /// ```
+    pub fn semantic_eq(&self, other: &Value) -> bool {
+        fn fixed(value: &Value) -> Option<Value> {
+            match value {
+                Value::Choice(choice) => choice.fixed(),
+                _ => None,
+            }
+        }
+
+        match (fixed(self), fixed(other)) {
+            (Some(this), Some(other)) => return this == other,
+            (Some(this), None) => return this.semantic_eq(other),
+            (None, Some(other)) => return self.semantic_eq(&other),
+            (None, None) => {}
+        }
+
+        match (self, other) {
+            (Value::Struct(this), Value::Struct(other)) => {
+                this.len() == other.len()
+                    && this
+                        .iter()
+                        .zip(other.iter())
+                        .all(|(this, other)| this.semantic_eq(other))
+            }
+            (Value::Object(this), Value::Object(other)) => {
+                this.type_ == other.type_
+                    && this.id == other.id
+                    && this.properties.len() == other.properties.len()
+                    && this
+                        .properties
+                        .iter()
+                        .zip(other.properties.iter())
+                        .all(|(this, other)| {
+                            this.key == other.key
+                                && this.flags == other.flags
+                                && this.value.semantic_eq(&other.value)
+                        })
+            }
+            (this, other) => this == other,
+        }
+    }
+}
+
+/// The alignment of pods in bytes.
+///
+/// Every pod starts at a multiple of this, including pods contained in other pods.
+/// Pod bodies are padded to reach this alignment.
+pub const POD_ALIGN: usize = 8;
+
+/// Round `len` up to the next multiple of [`POD_ALIGN`].
+///
+/// This is the size occupied by a pod body of length `len` once it has been padded,
+/// so the amount of padding bytes is `align_up(len) - len`.
+///
+/// # Examples
+/// ```rust
+/// use libspa::pod::align_up;
+///
+/// assert_eq!(align_up(0), 0);
+/// assert_eq!(align_up(4), 8);
+/// assert_eq!(align_up(8), 8);
+/// assert_eq!(align_up(13), 16);
+/// ```
+pub const fn align_up(len: usize) -> usize {
+    (len + POD_ALIGN - 1) & !(POD_ALIGN - 1)
 }
 
 /// an array of same type objects.
@@ -671,6 +957,94 @@ pub enum ValueArray {
     Fd(Vec<Fd>),
 }
 
+impl ValueArray {
+    /// Get the number of elements of the array.
+    pub fn len(&self) -> usize {
+        match self {
+            ValueArray::None(arr) => arr.len(),
+            ValueArray::Bool(arr) => arr.len(),
+            ValueArray::Id(arr) => arr.len(),
+            ValueArray::Int(arr) => arr.len(),
+            ValueArray::Long(arr) => arr.len(),
+            ValueArray::Float(arr) => arr.len(),
+            ValueArray::Double(arr) => arr.len(),
+            ValueArray::Rectangle(arr) => arr.len(),
+            ValueArray::Fraction(arr) => arr.len(),
+            ValueArray::Fd(arr) => arr.len(),
+        }
+    }
+
+    /// Returns `true` if the array has no element.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Implement `From<$type> for $target` by wrapping the value into the `$variant` variant.
+macro_rules! impl_from_for_variant {
+    ($target:ident { $($type:ty => $variant:ident,)* }) => {
+        $(
+            impl From<$type> for $target {
+                fn from(value: $type) -> Self {
+                    $target::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_variant!(Value {
+    bool => Bool,
+    Id => Id,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    String => String,
+    Vec<u8> => Bytes,
+    Rectangle => Rectangle,
+    Fraction => Fraction,
+    Fd => Fd,
+    ValueArray => ValueArray,
+    Vec<Value> => Struct,
+    Object => Object,
+    ChoiceValue => Choice,
+});
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl_from_for_variant!(ValueArray {
+    Vec<()> => None,
+    Vec<bool> => Bool,
+    Vec<Id> => Id,
+    Vec<i32> => Int,
+    Vec<i64> => Long,
+    Vec<f32> => Float,
+    Vec<f64> => Double,
+    Vec<Rectangle> => Rectangle,
+    Vec<Fraction> => Fraction,
+    Vec<Fd> => Fd,
+});
+
+/// Implement `From<Vec<T>> for Value`, producing a `ValueArray`.
+macro_rules! impl_from_vec_for_value {
+    ($($type:ty),*) => {
+        $(
+            impl From<Vec<$type>> for Value {
+                fn from(value: Vec<$type>) -> Self {
+                    Value::ValueArray(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_vec_for_value!((), bool, Id, i32, i64, f32, f64, Rectangle, Fraction, Fd);
+
 /// A typed choice.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChoiceValue {
@@ -692,6 +1066,57 @@ pub enum ChoiceValue {
     Fd(Choice<Fd>),
 }
 
+impl_from_for_variant!(ChoiceValue {
+    Choice<i32> => Int,
+    Choice<i64> => Long,
+    Choice<f32> => Float,
+    Choice<f64> => Double,
+    Choice<Id> => Id,
+    Choice<Rectangle> => Rectangle,
+    Choice<Fraction> => Fraction,
+    Choice<Fd> => Fd,
+});
+
+/// Implement `From<Choice<T>> for Value`, producing a `Choice`.
+macro_rules! impl_from_choice_for_value {
+    ($($type:ty),*) => {
+        $(
+            impl From<Choice<$type>> for Value {
+                fn from(value: Choice<$type>) -> Self {
+                    Value::Choice(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_choice_for_value!(i32, i64, f32, f64, Id, Rectangle, Fraction, Fd);
+
+impl ChoiceValue {
+    /// Get the value of a choice of type `None` as a scalar [`Value`].
+    ///
+    /// Returns `None` if the choice is of any other type.
+    pub fn fixed(&self) -> Option<Value> {
+        fn none<T: CanonicalFixedSizedPod + Copy>(choice: &Choice<T>) -> Option<T> {
+            match choice.1 {
+                ChoiceEnum::None(value) => Some(value),
+                _ => None,
+            }
+        }
+
+        match self {
+            ChoiceValue::Int(choice) => none(choice).map(Value::Int),
+            ChoiceValue::Long(choice) => none(choice).map(Value::Long),
+            ChoiceValue::Float(choice) => none(choice).map(Value::Float),
+            ChoiceValue::Double(choice) => none(choice).map(Value::Double),
+            ChoiceValue::Id(choice) => none(choice).map(Value::Id),
+            ChoiceValue::Rectangle(choice) => none(choice).map(Value::Rectangle),
+            ChoiceValue::Fraction(choice) => none(choice).map(Value::Fraction),
+            ChoiceValue::Fd(choice) => none(choice).map(Value::Fd),
+        }
+    }
+}
+
 /// An object from a pod.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Object {
@@ -703,6 +1128,43 @@ pub struct Object {
     pub properties: Vec<Property>,
 }
 
+impl Object {
+    /// Get the value of the property with the given key, if present.
+    pub fn get(&self, key: u32) -> Option<&Value> {
+        self.properties
+            .iter()
+            .find(|prop| prop.key == key)
+            .map(|prop| &prop.value)
+    }
+
+    /// Get the value of the property with the given short key name, e.g. `"volume"` for a `Props` object.
+    ///
+    /// See [`key_from_name`](`crate::param::key_from_name`) for the object types whose key names are known.
+    ///
+    /// Returns `None` if the name is not known for the object type or if the property is not present.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libspa::pod::{Object, Property, PropertyFlags, Value};
+    ///
+    /// let props = Object {
+    ///     type_: spa_sys::SPA_TYPE_OBJECT_Props,
+    ///     id: spa_sys::spa_param_type_SPA_PARAM_Props,
+    ///     properties: vec![Property {
+    ///         key: spa_sys::spa_prop_SPA_PROP_volume,
+    ///         flags: PropertyFlags::empty(),
+    ///         value: Value::Float(0.5),
+    ///     }],
+    /// };
+    ///
+    /// assert_eq!(props.get_by_name("volume"), Some(&Value::Float(0.5)));
+    /// assert_eq!(props.get_by_name("mute"), None);
+    /// ```
+    pub fn get_by_name(&self, name: &str) -> Option<&Value> {
+        crate::param::key_from_name(self.type_, name).and_then(|key| self.get(key))
+    }
+}
+
 /// An object property.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Property {
@@ -727,3 +1189,128 @@ bitflags! {
         const HINT_DICT = spa_sys::SPA_POD_PROP_FLAG_HINT_DICT;
     }
 }
+
+/// A macro for creating a [`Value::Object`] with predefined properties.
+///
+/// The macro accepts the type and the id of the object, followed by a list of `Key => Value` pairs
+/// seperated by commas, in braces.
+///
+/// Any expression that evaluates to a `u32` can be used for the keys,
+/// and any expression that evaluates to a `impl Into<Value>` can be used for the values.
+/// The properties are created without flags.
+///
+/// # Examples:
+/// ```rust
+/// use libspa::pod::{Object, Property, PropertyFlags, Value};
+/// use libspa::pod_object;
+///
+/// let props = pod_object!(
+///     spa_sys::SPA_TYPE_OBJECT_Props,
+///     spa_sys::spa_param_type_SPA_PARAM_Props,
+///     {
+///         spa_sys::spa_prop_SPA_PROP_volume => 0.5f32,
+///         spa_sys::spa_prop_SPA_PROP_mute => false,
+///     }
+/// );
+///
+/// assert_eq!(
+///     props,
+///     Value::Object(Object {
+///         type_: spa_sys::SPA_TYPE_OBJECT_Props,
+///         id: spa_sys::spa_param_type_SPA_PARAM_Props,
+///         properties: vec![
+///             Property {
+///                 key: spa_sys::spa_prop_SPA_PROP_volume,
+///                 flags: PropertyFlags::empty(),
+///                 value: Value::Float(0.5),
+///             },
+///             Property {
+///                 key: spa_sys::spa_prop_SPA_PROP_mute,
+///                 flags: PropertyFlags::empty(),
+///                 value: Value::Bool(false),
+///             },
+///         ],
+///     })
+/// );
+/// ```
+#[macro_export]
+macro_rules! pod_object {
+    ($type_:expr, $id:expr, {$($k:expr => $v:expr),* $(,)?} $(,)?) => {
+        $crate::pod::Value::Object($crate::pod::Object {
+            type_: $type_,
+            id: $id,
+            properties: vec![
+                $(
+                    $crate::pod::Property {
+                        key: $k,
+                        flags: $crate::pod::PropertyFlags::empty(),
+                        value: $crate::pod::Value::from($v),
+                    }
+                ),*
+            ],
+        })
+    };
+}
+
+/// Serialize `pod` into a writer that does not implement [`Seek`], such as a socket.
+///
+/// [`PodSerializer::serialize`] needs to seek back to write the size of structs and objects,
+/// so the pod is serialized into a buffer first, and then written to `writer` once it is complete.
+///
+/// The function returns back the writer and the number of bytes written,
+/// or an error if serialization or writing failed.
+///
+/// # Examples
+/// ```rust
+/// use libspa::pod::{serialize_to_writer, Value};
+///
+/// // `Vec<u8>` implements `Write`, but not `Seek`.
+/// let (pod, len) = serialize_to_writer(Vec::new(), &Value::Int(42)).unwrap();
+///
+/// assert_eq!(len, 16);
+/// assert_eq!(pod.len(), 16);
+/// ```
+pub fn serialize_to_writer<W, P>(
+    mut writer: W,
+    pod: &P,
+) -> Result<(W, u64), serialize::PodSerializeError>
+where
+    W: Write,
+    P: PodSerialize + ?Sized,
+{
+    let (buffer, len) = PodSerializer::serialize(Cursor::new(Vec::new()), pod)?;
+    writer
+        .write_all(&buffer.into_inner())
+        .map_err(GenError::IoError)?;
+
+    Ok((writer, len))
+}
+
+/// Get the total size in bytes of the raw pod at the start of `input`, including its header and padding.
+///
+/// Only the header of the pod is read, the body is not validated.
+///
+/// Returns `None` if `input` is too short to contain the full pod.
+///
+/// # Examples
+/// ```rust
+/// use std::io::Cursor;
+/// use libspa::pod::{pod_size, serialize::PodSerializer};
+///
+/// let (cursor, _) = PodSerializer::serialize(Cursor::new(Vec::new()), "foo").unwrap();
+/// let pod = cursor.into_inner();
+///
+/// // 8 bytes header, 4 bytes body and 4 bytes padding.
+/// assert_eq!(pod_size(&pod), Some(16));
+/// assert_eq!(pod_size(&pod[..12]), None);
+/// ```
+pub fn pod_size(input: &[u8]) -> Option<usize> {
+    let (_, body_size) = u32::<_, ()>(Endianness::Native)(input).ok()?;
+    let size = 8 + align_up(body_size as usize);
+
+    if input.len() < size {
+        None
+    } else {
+        Some(size)
+    }
+}
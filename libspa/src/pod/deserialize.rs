@@ -9,7 +9,14 @@
 //! You can also implement the [`PodDeserialize`] trait on another type yourself. See the traits documentation for more
 //! information on how to do that.
 
-use std::{convert::Infallible, ffi::c_void, marker::PhantomData, ptr};
+use std::{
+    borrow::Cow,
+    convert::{Infallible, TryFrom, TryInto},
+    ffi::c_void,
+    marker::PhantomData,
+    os::unix::io::RawFd,
+    ptr,
+};
 
 use nom::{
     bytes::complete::{tag, take},
@@ -20,7 +27,8 @@ use nom::{
 };
 
 use super::{
-    CanonicalFixedSizedPod, ChoiceValue, FixedSizedPod, Object, PropertyFlags, Value, ValueArray,
+    align_up, CanonicalFixedSizedPod, ChoiceValue, FixedSizedPod, Object, PropertyFlags, Value,
+    ValueArray,
 };
 use crate::{
     pod::Property,
@@ -200,6 +208,21 @@ impl<'de> PodDeserialize<'de> for Vec<u8> {
     }
 }
 
+// Deserialize a `Bytes` pod. The returned `Cow` is borrowed, so this is zero-copy (is a slice of the input),
+// but it can be turned into owned bytes later on if needed.
+impl<'de> PodDeserialize<'de> for Cow<'de, [u8]> {
+    fn deserialize(
+        deserializer: PodDeserializer<'de>,
+    ) -> Result<(Self, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>>
+    where
+        Self: Sized,
+    {
+        deserializer
+            .deserialize_bytes(BytesVisitor)
+            .map(|(b, success)| (Cow::Borrowed(b), success))
+    }
+}
+
 // Deserialize an `Array` type pod.
 impl<'de, P: FixedSizedPod + CanonicalFixedSizedPod + std::marker::Copy> PodDeserialize<'de>
     for Vec<P>
@@ -214,6 +237,34 @@ impl<'de, P: FixedSizedPod + CanonicalFixedSizedPod + std::marker::Copy> PodDese
     }
 }
 
+// Deserialize an `Array` type pod with exactly `N` elements.
+impl<'de, P: FixedSizedPod, const N: usize> PodDeserialize<'de> for [P; N] {
+    fn deserialize(
+        deserializer: PodDeserializer<'de>,
+    ) -> Result<(Self, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>>
+    where
+        Self: Sized,
+    {
+        let (mut array_deserializer, length) = deserializer.new_array_deserializer::<P>()?;
+        if length as usize != N {
+            return Err(DeserializeError::WrongArrayLength(length));
+        }
+
+        let mut elements = Vec::with_capacity(N);
+        for _ in 0..N {
+            elements.push(array_deserializer.deserialize_element()?);
+        }
+        let success = array_deserializer.end()?;
+
+        let array = match elements.try_into() {
+            Ok(array) => array,
+            Err(_) => unreachable!("Array has exactly N elements"),
+        };
+
+        Ok((array, success))
+    }
+}
+
 /// This struct is returned by [`PodDeserialize`] implementors on deserialization sucess.
 ///
 /// Because this can only be constructed by the [`PodDeserializer`], [`PodDeserialize`] implementors are forced
@@ -223,6 +274,8 @@ pub struct DeserializeSuccess<'de>(PodDeserializer<'de>);
 /// This struct is responsible for deserializing a raw pod into a [`PodDeserialize`] implementor.
 pub struct PodDeserializer<'de> {
     input: &'de [u8],
+    /// Whether pods of unsupported types are deserialized as [`Value::Unknown`] instead of failing.
+    keep_unknown: bool,
 }
 
 impl<'de, 'a> PodDeserializer<'de> {
@@ -238,10 +291,43 @@ impl<'de, 'a> PodDeserializer<'de> {
     pub fn deserialize_from<P: PodDeserialize<'de>>(
         input: &'de [u8],
     ) -> Result<(&'de [u8], P), DeserializeError<&'de [u8]>> {
-        let deserializer = Self { input };
+        let deserializer = Self {
+            input,
+            keep_unknown: false,
+        };
         P::deserialize(deserializer).map(|(res, success)| (success.0.input, res))
     }
 
+    /// Variant of [`Self::deserialize_from`] returning the number of bytes consumed from the input
+    /// instead of the remaining input.
+    ///
+    /// This is useful when reading multiple consecutive pods from a buffer,
+    /// as the returned length can be used to advance to the next pod directly.
+    pub fn deserialize_from_len<P: PodDeserialize<'de>>(
+        input: &'de [u8],
+    ) -> Result<(usize, P), DeserializeError<&'de [u8]>> {
+        Self::deserialize_from(input).map(|(remaining, res)| (input.len() - remaining.len(), res))
+    }
+
+    /// Iterate over the properties of the object pod at the start of `input`.
+    ///
+    /// Properties are deserialized on demand, so that no memory needs to be allocated for the properties
+    /// that are not needed, e.g. when looking for a single property.
+    ///
+    /// # Errors
+    /// Returns a parsing error if input does not start with an object pod.
+    pub fn iter_object(input: &'de [u8]) -> Result<ObjectIter<'de>, DeserializeError<&'de [u8]>> {
+        let deserializer = Self {
+            input,
+            keep_unknown: false,
+        };
+
+        Ok(ObjectIter {
+            deserializer: deserializer.new_object_deserializer()?,
+            failed: false,
+        })
+    }
+
     /// Deserialize a `spa_sys::spa_pod` pointer.
     ///
     /// # Safety
@@ -295,11 +381,8 @@ impl<'de, 'a> PodDeserializer<'de> {
     fn deserialize_fixed_sized_pod<P: FixedSizedPod>(
         mut self,
     ) -> Result<(P, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>> {
-        let padding = if 8 - (P::CanonicalType::SIZE % 8) == 8 {
-            0
-        } else {
-            8 - (P::CanonicalType::SIZE % 8)
-        };
+        let size = P::CanonicalType::SIZE as usize;
+        let padding = align_up(size) - size;
 
         self.parse(delimited(
             Self::header(P::CanonicalType::TYPE),
@@ -393,7 +476,7 @@ impl<'de, 'a> PodDeserializer<'de> {
         V: Visitor<'de>,
     {
         let len = self.parse(Self::header(spa_sys::SPA_TYPE_String))?;
-        let padding = (8 - len) % 8;
+        let padding = align_up(len as usize) - len as usize;
         let res = self.parse(terminated(
             map_res(terminated(take(len - 1), tag([b'\0'])), std::str::from_utf8),
             take(padding),
@@ -410,7 +493,7 @@ impl<'de, 'a> PodDeserializer<'de> {
         V: Visitor<'de>,
     {
         let len = self.parse(Self::header(spa_sys::SPA_TYPE_Bytes))?;
-        let padding = (8 - len) % 8;
+        let padding = align_up(len as usize) - len as usize;
         let res = self.parse(terminated(take(len), take(padding)))?;
         Ok((visitor.visit_bytes(res)?, DeserializeSuccess(self)))
     }
@@ -431,8 +514,10 @@ impl<'de, 'a> PodDeserializer<'de> {
             *len == E::CanonicalType::SIZE
         }))?;
 
+        // The body of the array also contains the header of the child, which has already been parsed.
+        let elements_len = len.saturating_sub(8);
         let num_elems = if E::CanonicalType::SIZE != 0 {
-            (len - 8) / E::CanonicalType::SIZE
+            elements_len / E::CanonicalType::SIZE
         } else {
             0
         };
@@ -440,6 +525,7 @@ impl<'de, 'a> PodDeserializer<'de> {
         Ok((
             ArrayPodDeserializer {
                 deserializer: self,
+                elements_len,
                 length: num_elems,
                 deserialized: 0,
                 _phantom: PhantomData,
@@ -598,6 +684,7 @@ impl<'de, 'a> PodDeserializer<'de> {
         // re-use the array deserializer as choice values are serialized the same way
         let mut array_deserializer = ArrayPodDeserializer {
             deserializer: self,
+            elements_len: num_values * E::CanonicalType::SIZE,
             length: num_values,
             deserialized: 0,
             _phantom: PhantomData,
@@ -627,7 +714,12 @@ impl<'de, 'a> PodDeserializer<'de> {
             self.parse(pair(u32(Endianness::Native), u32(Endianness::Native)))?;
         let (child_size, child_type) =
             self.parse(pair(u32(Endianness::Native), u32(Endianness::Native)))?;
-        let num_values = (len - 16) / child_size;
+        // Like for arrays, zero sized children such as `None` can not have any value.
+        let num_values = if child_size != 0 {
+            len.saturating_sub(16) / child_size
+        } else {
+            0
+        };
 
         fn create_choice<'de, E>(
             choice_type: u32,
@@ -747,7 +839,7 @@ impl<'de, 'a> PodDeserializer<'de> {
                 let choice = create_choice(choice_type, values, flags)?;
                 Ok((visitor.visit_choice_fd(choice)?, success))
             }
-            _ => Err(DeserializeError::InvalidType),
+            _ => Err(DeserializeError::UnsupportedChoiceElementType(child_type)),
         }
     }
 
@@ -803,10 +895,28 @@ impl<'de, 'a> PodDeserializer<'de> {
             spa_sys::SPA_TYPE_Object => self.deserialize_object(ValueVisitor),
             spa_sys::SPA_TYPE_Choice => self.deserialize_choice(ValueVisitor),
             spa_sys::SPA_TYPE_Pointer => self.deserialize_pointer(ValueVisitor),
+            _ if self.keep_unknown => self.deserialize_unknown(),
             _ => Err(DeserializeError::InvalidType),
         }
     }
 
+    /// Deserialize a pod of any type as a [`Value::Unknown`] holding its raw body.
+    fn deserialize_unknown(
+        mut self,
+    ) -> Result<(Value, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>> {
+        let (len, type_) = self.parse(pair(u32(Endianness::Native), u32(Endianness::Native)))?;
+        let padding = align_up(len as usize) - len as usize;
+        let body = self.parse(terminated(take(len), take(padding)))?;
+
+        Ok((
+            Value::Unknown {
+                type_,
+                body: body.to_vec(),
+            },
+            DeserializeSuccess(self),
+        ))
+    }
+
     fn deserialize_array_any(
         self,
     ) -> Result<(Value, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>> {
@@ -863,6 +973,7 @@ impl<'de, 'a> PodDeserializer<'de> {
                 let array = ValueArrayFdVisitor.visit_array(elements)?;
                 (array, success)
             }
+            _ if self.keep_unknown => return self.deserialize_unknown(),
             _ => return Err(DeserializeError::InvalidType),
         };
 
@@ -870,11 +981,101 @@ impl<'de, 'a> PodDeserializer<'de> {
     }
 
     /// Variant of [`Self::deserialize_from`] returning the parsed value as a [`Value`].
+    ///
+    /// The returned [`Value`] owns its data, so strings and bytes are copied out of `input`.
+    /// Deserialize into a typed `&[u8]` or `Cow<[u8]>` with [`Self::deserialize_from`] to borrow bytes instead.
     pub fn deserialize_any_from(
         input: &'de [u8],
     ) -> Result<(&'de [u8], Value), DeserializeError<&'de [u8]>> {
         Self::deserialize_from(input)
     }
+
+    /// Variant of [`Self::deserialize_any_from`] keeping the pods of unsupported types
+    /// as [`Value::Unknown`] instead of failing.
+    ///
+    /// This allows deserializing the rest of a pod containing a type that is not supported,
+    /// such as a field of a struct or a property of an object using a type added by a newer version of SPA.
+    pub fn deserialize_any_from_keep_unknown(
+        input: &'de [u8],
+    ) -> Result<(&'de [u8], Value), DeserializeError<&'de [u8]>> {
+        let deserializer = Self {
+            input,
+            keep_unknown: true,
+        };
+        Value::deserialize(deserializer).map(|(res, success)| (success.0.input, res))
+    }
+
+    /// Variant of [`Self::deserialize_any_from`] resolving the `Fd` pods to actual file descriptors.
+    ///
+    /// The value of an `Fd` pod is not a file descriptor, but the index of a file descriptor
+    /// sent alongside the message containing the pod, such as the `fds` of a DMA-BUF or memfd buffer.
+    /// Each `Fd` found in the value, including in arrays, structs, objects and choices, is replaced
+    /// by the file descriptor at its index in `fds`.
+    ///
+    /// The file descriptors are not duplicated, so they are still owned by the caller.
+    ///
+    /// # Errors
+    /// Returns [`DeserializeError::InvalidFd`] if an `Fd` pod refers to an index outside of `fds`.
+    pub fn deserialize_from_with_fds(
+        input: &'de [u8],
+        fds: &[RawFd],
+    ) -> Result<(&'de [u8], Value), DeserializeError<&'de [u8]>> {
+        fn resolve(fd: &mut Fd, fds: &[RawFd]) -> Result<(), i64> {
+            let raw = usize::try_from(fd.0)
+                .ok()
+                .and_then(|index| fds.get(index))
+                .ok_or(fd.0)?;
+            *fd = Fd(i64::from(*raw));
+            Ok(())
+        }
+
+        fn resolve_all<'a>(
+            mut values: impl Iterator<Item = &'a mut Fd>,
+            fds: &[RawFd],
+        ) -> Result<(), i64> {
+            values.try_for_each(|fd| resolve(fd, fds))
+        }
+
+        fn resolve_value(value: &mut Value, fds: &[RawFd]) -> Result<(), i64> {
+            match value {
+                Value::Fd(fd) => resolve(fd, fds),
+                Value::ValueArray(ValueArray::Fd(array)) => resolve_all(array.iter_mut(), fds),
+                Value::Struct(fields) => fields
+                    .iter_mut()
+                    .try_for_each(|field| resolve_value(field, fds)),
+                Value::Object(object) => object
+                    .properties
+                    .iter_mut()
+                    .try_for_each(|prop| resolve_value(&mut prop.value, fds)),
+                Value::Choice(ChoiceValue::Fd(Choice(_, choice))) => match choice {
+                    ChoiceEnum::None(fd) => resolve(fd, fds),
+                    ChoiceEnum::Range { default, min, max } => {
+                        resolve_all(vec![default, min, max].into_iter(), fds)
+                    }
+                    ChoiceEnum::Step {
+                        default,
+                        min,
+                        max,
+                        step,
+                    } => resolve_all(vec![default, min, max, step].into_iter(), fds),
+                    ChoiceEnum::Enum {
+                        default,
+                        alternatives: others,
+                    }
+                    | ChoiceEnum::Flags {
+                        default,
+                        flags: others,
+                    } => resolve_all(std::iter::once(default).chain(others.iter_mut()), fds),
+                },
+                _ => Ok(()),
+            }
+        }
+
+        let (remaining, mut value) = Self::deserialize_any_from(input)?;
+        resolve_value(&mut value, fds).map_err(DeserializeError::InvalidFd)?;
+
+        Ok((remaining, value))
+    }
 }
 
 /// This struct handles deserializing arrays.
@@ -886,6 +1087,8 @@ impl<'de, 'a> PodDeserializer<'de> {
 /// followed by calling its [`end`](`Self::end`) function to finish deserialization of the array.
 pub struct ArrayPodDeserializer<'de, E: FixedSizedPod> {
     deserializer: PodDeserializer<'de>,
+    // The size of the elements in the array body in bytes, excluding padding.
+    elements_len: u32,
     // The total number of elements that must be deserialized from this array.
     length: u32,
     // The number of elements that have been deserialized so far.
@@ -901,7 +1104,7 @@ impl<'de, E: FixedSizedPod> ArrayPodDeserializer<'de, E> {
     /// # Panics
     /// Panics if there are no elements left to deserialize.
     pub fn deserialize_element(&mut self) -> Result<E, DeserializeError<&'de [u8]>> {
-        if !self.deserialized < self.length {
+        if self.deserialized >= self.length {
             panic!("No elements left in the pod to deserialize");
         }
 
@@ -925,14 +1128,12 @@ impl<'de, E: FixedSizedPod> ArrayPodDeserializer<'de, E> {
             "Not all fields were deserialized from the array pod"
         );
 
-        // Deserialize remaining padding bytes.
-        let bytes_read = self.deserialized * E::CanonicalType::SIZE;
-        let padding = if bytes_read % 8 == 0 {
-            0
-        } else {
-            8 - (bytes_read as usize % 8)
-        };
-        self.deserializer.parse(take(padding))?;
+        // Deserialize remaining bytes of the body, which do not form a complete element, and the padding.
+        // Computing this from the size of the body rather than the number of elements read
+        // ensures the whole pod is consumed, even for arrays of zero sized elements like `None`.
+        let bytes_read = (self.deserialized * E::CanonicalType::SIZE) as usize;
+        let remaining = align_up(self.elements_len as usize) - bytes_read;
+        self.deserializer.parse(take(remaining))?;
 
         Ok(DeserializeSuccess(self.deserializer))
     }
@@ -1061,6 +1262,21 @@ impl<'de> ObjectPodDeserializer<'de> {
         }
     }
 
+    /// Variant of [`Self::deserialize_property`] deserializing the property value as a [`Value`].
+    ///
+    /// This can be used to iterate over all properties of an object in order,
+    /// including those with keys unknown to the caller.
+    ///
+    /// Returns `Some` with the key, flags and value of the property when a property was successfully deserialized
+    /// and `None` when all properties have been read.
+    #[allow(clippy::type_complexity)]
+    pub fn deserialize_any_property(
+        &mut self,
+    ) -> Result<Option<(u32, PropertyFlags, Value)>, DeserializeError<&'de [u8]>> {
+        self.deserialize_property::<Value>()
+            .map(|prop| prop.map(|(value, key, flags)| (key, flags, value)))
+    }
+
     /// Variant of [`Self::deserialize_property`] ensuring the property has a given key.
     ///
     /// Returns [`DeserializeError::PropertyMissing`] if the property is missing
@@ -1098,6 +1314,49 @@ impl<'de> ObjectPodDeserializer<'de> {
         )))
     }
 }
+
+/// An iterator over the properties of an object pod, deserializing them on demand.
+///
+/// It can be obtained by calling [`PodDeserializer::iter_object`].
+///
+/// Each item is the key, flags and value of a property, or an error if the property could not be deserialized,
+/// in which case the iteration ends.
+pub struct ObjectIter<'de> {
+    deserializer: ObjectPodDeserializer<'de>,
+    /// Set once deserializing a property failed, as the deserializer can not be used anymore afterwards.
+    failed: bool,
+}
+
+impl<'de> ObjectIter<'de> {
+    /// Get the type of the object.
+    pub fn object_type(&self) -> u32 {
+        self.deserializer.object_type
+    }
+
+    /// Get the id of the object.
+    pub fn object_id(&self) -> u32 {
+        self.deserializer.object_id
+    }
+}
+
+impl<'de> Iterator for ObjectIter<'de> {
+    type Item = Result<(u32, PropertyFlags, Value), DeserializeError<&'de [u8]>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        match self.deserializer.deserialize_any_property() {
+            Ok(prop) => prop.map(Ok),
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 /// Represent an error raised when deserializing a pod
 pub enum DeserializeError<I> {
@@ -1111,10 +1370,21 @@ pub enum DeserializeError<I> {
     PropertyMissing,
     /// The property does not have the expected key
     PropertyWrongKey(u32),
+    /// The array does not have the expected number of elements
+    WrongArrayLength(u32),
     /// Invalid choice type
     InvalidChoiceType,
     /// Values are missing in the choice pod
     MissingChoiceValues,
+    /// The choice pod contains values of a type that can not be part of a choice, such as `Bool` or `String`.
+    ///
+    /// Contains the type of the values, e.g. `spa_sys::SPA_TYPE_Bool`.
+    UnsupportedChoiceElementType(u32),
+    /// An `Fd` pod refers to a file descriptor that was not provided.
+    ///
+    /// Contains the value of the pod.
+    /// See [`PodDeserializer::deserialize_from_with_fds`].
+    InvalidFd(i64),
 }
 
 impl<I> From<nom::Err<nom::error::Error<I>>> for DeserializeError<I> {
@@ -1530,7 +1800,7 @@ impl<'de> Visitor<'de> for ValueVisitor {
     ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
         let mut properties = Vec::new();
 
-        while let Some((value, key, flags)) = object_deserializer.deserialize_property()? {
+        while let Some((key, flags, value)) = object_deserializer.deserialize_any_property()? {
             let prop = Property { key, flags, value };
             properties.push(prop);
         }
@@ -0,0 +1,290 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Conversion of [`Value`]s into JSON.
+//!
+//! This module is only available when the `serde_json` feature is enabled.
+//!
+//! The produced JSON is intended for debugging and exporting purposes and is similar to the output of `pw-dump`.
+//! The conversion is lossy, so the original [`Value`] can not be recovered from it.
+
+use serde_json::{json, Map, Number};
+
+use super::{ChoiceValue, Object, Value, ValueArray};
+use crate::utils::{Choice, ChoiceEnum, Fd, Fraction, Id, Rectangle};
+
+/// Convert a fixed sized value into a JSON value.
+trait ToJson {
+    fn to_json(&self) -> serde_json::Value;
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Bool(*self)
+    }
+}
+
+impl ToJson for i32 {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::from(*self)
+    }
+}
+
+impl ToJson for i64 {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::from(*self)
+    }
+}
+
+impl ToJson for f32 {
+    fn to_json(&self) -> serde_json::Value {
+        f64::from(*self).to_json()
+    }
+}
+
+impl ToJson for f64 {
+    fn to_json(&self) -> serde_json::Value {
+        // JSON has no representation for NaN and infinite numbers.
+        Number::from_f64(*self).map_or(serde_json::Value::Null, serde_json::Value::Number)
+    }
+}
+
+impl ToJson for Id {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::from(self.0)
+    }
+}
+
+impl ToJson for Fd {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::from(self.0)
+    }
+}
+
+impl ToJson for Rectangle {
+    fn to_json(&self) -> serde_json::Value {
+        json!({ "width": self.width, "height": self.height })
+    }
+}
+
+impl ToJson for Fraction {
+    fn to_json(&self) -> serde_json::Value {
+        json!({ "num": self.num, "denom": self.denom })
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson + crate::pod::CanonicalFixedSizedPod> ToJson for Choice<T> {
+    fn to_json(&self) -> serde_json::Value {
+        match &self.1 {
+            ChoiceEnum::None(value) => json!({
+                "type": "None",
+                "default": value.to_json(),
+            }),
+            ChoiceEnum::Range { default, min, max } => json!({
+                "type": "Range",
+                "default": default.to_json(),
+                "min": min.to_json(),
+                "max": max.to_json(),
+            }),
+            ChoiceEnum::Step {
+                default,
+                min,
+                max,
+                step,
+            } => json!({
+                "type": "Step",
+                "default": default.to_json(),
+                "min": min.to_json(),
+                "max": max.to_json(),
+                "step": step.to_json(),
+            }),
+            ChoiceEnum::Enum {
+                default,
+                alternatives,
+            } => json!({
+                "type": "Enum",
+                "default": default.to_json(),
+                "alternatives": alternatives.to_json(),
+            }),
+            ChoiceEnum::Flags { default, flags } => json!({
+                "type": "Flags",
+                "default": default.to_json(),
+                "flags": flags.to_json(),
+            }),
+        }
+    }
+}
+
+impl ToJson for Object {
+    fn to_json(&self) -> serde_json::Value {
+        let properties = self
+            .properties
+            .iter()
+            .map(|prop| {
+                let key = crate::param::key_name(self.type_, prop.key)
+                    .map_or_else(|| prop.key.to_string(), str::to_string);
+                (key, Value::to_json(&prop.value))
+            })
+            .collect::<Map<_, _>>();
+
+        serde_json::Value::Object(properties)
+    }
+}
+
+impl Value {
+    /// Convert the value into a [`serde_json::Value`].
+    ///
+    /// The values are mapped as follows:
+    /// - `None` becomes `null`, and `Bool`, `Id`, `Int`, `Long`, `Float`, `Double`, `String` and `Fd` become
+    ///   their corresponding JSON primitive.
+    ///   `NaN` and infinite floating point numbers are not representable in JSON and become `null`.
+    /// - `Bytes`, `ValueArray` and `Struct` become arrays.
+    /// - `Rectangle` becomes `{ "width": .., "height": .. }` and `Fraction` becomes `{ "num": .., "denom": .. }`.
+    /// - `Object` becomes an object mapping each property key to its value.
+    ///   Keys are resolved to their name when known, such as `"volume"` or `"audio.rate"`
+    ///   (see [`key_name`](`crate::param::key_name`)), and are otherwise written as numbers.
+    /// - `Choice` becomes an object tagged with the choice type, e.g.
+    ///   `{ "type": "Range", "default": .., "min": .., "max": .. }`.
+    /// - `Pointer` becomes `{ "type": .., "value": .. }` with the address of the pointer as its value.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libspa::pod::Value;
+    /// use serde_json::json;
+    ///
+    /// let value = Value::Struct(vec![Value::Int(1), Value::String("foo".into())]);
+    /// assert_eq!(value.to_json(), json!([1, "foo"]));
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::None => ().to_json(),
+            Value::Bool(b) => b.to_json(),
+            Value::Id(id) => id.to_json(),
+            Value::Int(i) => i.to_json(),
+            Value::Long(l) => l.to_json(),
+            Value::Float(f) => f.to_json(),
+            Value::Double(d) => d.to_json(),
+            Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::Bytes(b) => serde_json::Value::from(b.as_slice()),
+            Value::Rectangle(rect) => rect.to_json(),
+            Value::Fraction(frac) => frac.to_json(),
+            Value::Fd(fd) => fd.to_json(),
+            Value::ValueArray(array) => match array {
+                ValueArray::None(arr) => arr.to_json(),
+                ValueArray::Bool(arr) => arr.to_json(),
+                ValueArray::Id(arr) => arr.to_json(),
+                ValueArray::Int(arr) => arr.to_json(),
+                ValueArray::Long(arr) => arr.to_json(),
+                ValueArray::Float(arr) => arr.to_json(),
+                ValueArray::Double(arr) => arr.to_json(),
+                ValueArray::Rectangle(arr) => arr.to_json(),
+                ValueArray::Fraction(arr) => arr.to_json(),
+                ValueArray::Fd(arr) => arr.to_json(),
+            },
+            Value::Struct(fields) => {
+                serde_json::Value::Array(fields.iter().map(Value::to_json).collect())
+            }
+            Value::Object(object) => object.to_json(),
+            Value::Choice(choice) => match choice {
+                ChoiceValue::Int(choice) => choice.to_json(),
+                ChoiceValue::Long(choice) => choice.to_json(),
+                ChoiceValue::Float(choice) => choice.to_json(),
+                ChoiceValue::Double(choice) => choice.to_json(),
+                ChoiceValue::Id(choice) => choice.to_json(),
+                ChoiceValue::Rectangle(choice) => choice.to_json(),
+                ChoiceValue::Fraction(choice) => choice.to_json(),
+                ChoiceValue::Fd(choice) => choice.to_json(),
+            },
+            Value::Pointer(type_, ptr) => json!({
+                "type": type_,
+                "value": *ptr as usize,
+            }),
+            Value::Unknown { type_, body } => json!({
+                "type": type_,
+                "body": body,
+            }),
+        }
+    }
+}
+
+impl From<&Value> for serde_json::Value {
+    fn from(value: &Value) -> Self {
+        value.to_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pod::{Property, PropertyFlags},
+        utils::ChoiceFlags,
+    };
+
+    #[test]
+    fn object() {
+        let value = Value::Object(Object {
+            type_: spa_sys::SPA_TYPE_OBJECT_Props,
+            id: spa_sys::spa_param_type_SPA_PARAM_Props,
+            properties: vec![
+                Property {
+                    key: spa_sys::spa_prop_SPA_PROP_device,
+                    flags: PropertyFlags::empty(),
+                    value: Value::String("hw:0".into()),
+                },
+                Property {
+                    key: spa_sys::spa_prop_SPA_PROP_volume,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Choice(ChoiceValue::Float(Choice(
+                        ChoiceFlags::empty(),
+                        ChoiceEnum::Range {
+                            default: 1.0,
+                            min: 0.0,
+                            max: 2.0,
+                        },
+                    ))),
+                },
+                Property {
+                    // A custom key, whose name is not known.
+                    key: spa_sys::spa_prop_SPA_PROP_START_CUSTOM,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Int(1),
+                },
+            ],
+        });
+
+        let mut expected = Map::new();
+        expected.insert("device".to_string(), json!("hw:0"));
+        expected.insert(
+            "volume".to_string(),
+            json!({ "type": "Range", "default": 1.0, "min": 0.0, "max": 2.0 }),
+        );
+        expected.insert(
+            spa_sys::spa_prop_SPA_PROP_START_CUSTOM.to_string(),
+            json!(1),
+        );
+
+        assert_eq!(value.to_json(), serde_json::Value::Object(expected));
+    }
+
+    #[test]
+    fn non_finite_float() {
+        assert_eq!(Value::Double(f64::NAN).to_json(), serde_json::Value::Null);
+        assert_eq!(
+            Value::ValueArray(ValueArray::Float(vec![1.5, f32::INFINITY])).to_json(),
+            json!([1.5, null])
+        );
+    }
+}
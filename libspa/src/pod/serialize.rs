@@ -12,7 +12,8 @@
 use std::{
     convert::TryInto,
     ffi::CString,
-    io::{Seek, SeekFrom, Write},
+    fmt,
+    io::{self, Seek, SeekFrom, Write},
     marker::PhantomData,
 };
 
@@ -28,10 +29,69 @@ use cookie_factory::{
 
 use crate::{
     pod::ChoiceValue,
-    utils::{Choice, ChoiceEnum},
+    utils::{Choice, ChoiceEnum, Id},
 };
 
-use super::{CanonicalFixedSizedPod, FixedSizedPod, PropertyFlags, Value, ValueArray};
+use super::{align_up, CanonicalFixedSizedPod, FixedSizedPod, PropertyFlags, Value, ValueArray};
+
+/// An error that occurred while serializing a pod.
+///
+/// This is returned by [`PodSerializer::serialize`] and [`serialize_to_writer`](`super::serialize_to_writer`),
+/// so that callers can match on the kind of failure without depending on `cookie_factory` themselves.
+///
+/// # Examples
+/// ```rust
+/// use std::io::Cursor;
+/// use libspa::pod::serialize::{PodSerializeError, PodSerializer};
+///
+/// let mut buffer = [0u8; 8];
+/// let res = PodSerializer::serialize(Cursor::new(&mut buffer[..]), &42i32);
+///
+/// assert!(matches!(res, Err(PodSerializeError::BufferTooSmall)));
+/// ```
+#[derive(Debug)]
+pub enum PodSerializeError {
+    /// The output does not have enough space left to hold the serialized pod.
+    BufferTooSmall,
+    /// Any other error that occurred during serialization.
+    ///
+    /// The underlying error, such as an [`io::Error`] of the output, is available through
+    /// [`Error::source`](`std::error::Error::source`).
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<GenError> for PodSerializeError {
+    fn from(error: GenError) -> Self {
+        match error {
+            GenError::BufferTooSmall(_) => Self::BufferTooSmall,
+            // Writing into a full `std::io::Write` implementor, such as a `Cursor<&mut [u8]>`,
+            // surfaces as an io error instead.
+            GenError::IoError(err) if err.kind() == io::ErrorKind::WriteZero => {
+                Self::BufferTooSmall
+            }
+            GenError::IoError(err) => Self::Other(Box::new(err)),
+            other => Self::Other(Box::new(other)),
+        }
+    }
+}
+
+impl fmt::Display for PodSerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BufferTooSmall => write!(f, "buffer too small to hold the serialized pod"),
+            Self::Other(_) => write!(f, "failed to serialize pod"),
+        }
+    }
+}
+
+impl std::error::Error for PodSerializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::BufferTooSmall => None,
+            Self::Other(err) => Some(&**err),
+        }
+    }
+}
 
 /// Implementors of this trait are able to serialize themselves into a SPA pod by using a [`PodSerializer`].
 ///
@@ -106,6 +166,35 @@ use super::{CanonicalFixedSizedPod, FixedSizedPod, PropertyFlags, Value, ValueAr
 ///     }
 /// }
 /// ```
+///
+/// Make an enum serialize into a `Struct` pod containing an `Id` pod telling the variants apart, followed by the
+/// payload of the variant.
+/// There is no `#[derive(PodSerialize)]`, so enums are implemented by hand using
+/// [`PodSerializer::serialize_tagged`]:
+/// ```rust
+/// use std::io;
+/// use libspa::{
+///     pod::serialize::{GenError, PodSerialize, PodSerializer, SerializeSuccess},
+///     utils::Id,
+/// };
+///
+/// enum Shape {
+///     Circle(f32),
+///     Square { side: i32 },
+/// }
+///
+/// impl PodSerialize for Shape {
+///     fn serialize<O: io::Write + io::Seek>(
+///         &self,
+///         serializer: PodSerializer<O>,
+///     ) -> Result<SerializeSuccess<O>, GenError> {
+///         match self {
+///             Shape::Circle(radius) => serializer.serialize_tagged(Id(0), radius),
+///             Shape::Square { side } => serializer.serialize_tagged(Id(1), side),
+///         }
+///     }
+/// }
+/// ```
 pub trait PodSerialize {
     /// Serialize the type by using the provided [`PodSerializer`]
     fn serialize<O: Write + Seek>(
@@ -202,6 +291,7 @@ impl PodSerialize for Value {
                 ChoiceValue::Fd(choice) => serializer.serialize_choice(choice),
             },
             Value::Pointer(type_, pointer) => serializer.serialize_pointer(*type_, *pointer),
+            Value::Unknown { type_, body } => serializer.serialize_raw(*type_, body),
         }
     }
 }
@@ -263,22 +353,26 @@ impl<O: Write + Seek> PodSerializer<O> {
     /// to provide the [`Seek`] trait.
     ///
     /// The function returns back the `out` writer and the number of bytes written,
-    /// or a generation error if serialization failed.
-    pub fn serialize<P>(out: O, pod: &P) -> Result<(O, u64), GenError>
+    /// or an error if serialization failed.
+    ///
+    /// The number of bytes written is the exact size of the pod, including its header and padding,
+    /// and is equal to [`Value::serialized_size`] for [`Value`]s.
+    /// As the pod is written at the current position of `out`, it can be used to find the end of the pod
+    /// when it is written into a larger message, such as after a length prefix.
+    pub fn serialize<P>(out: O, pod: &P) -> Result<(O, u64), PodSerializeError>
     where
         P: PodSerialize + ?Sized,
     {
         let serializer = Self { out: Some(out) };
 
-        pod.serialize(serializer).map(|success| {
-            (
-                success
-                    .serializer
-                    .out
-                    .expect("Serializer does not contain a writer"),
-                success.len,
-            )
-        })
+        let success = pod.serialize(serializer)?;
+        Ok((
+            success
+                .serializer
+                .out
+                .expect("Serializer does not contain a writer"),
+            success.len,
+        ))
     }
 
     /// Helper serialization method for serializing the Pod header.
@@ -319,7 +413,7 @@ impl<O: Write + Seek> PodSerializer<O> {
         type_: u32,
         f: impl SerializeFn<O>,
     ) -> Result<SerializeSuccess<O>, GenError> {
-        let padding = if size % 8 == 0 { 0 } else { 8 - (size % 8) };
+        let padding = align_up(size) - size;
         let written = self.gen(tuple((
             Self::header(size, type_),
             f,
@@ -384,6 +478,34 @@ impl<O: Write + Seek> PodSerializer<O> {
         })
     }
 
+    /// Serialize an `Array` pod holding the elements produced by `iter`.
+    ///
+    /// This avoids collecting computed elements into a [`Vec`] only to serialize it,
+    /// as the elements are written as they are produced.
+    ///
+    /// # Panics
+    /// Panics if the iterator does not produce exactly the number of elements reported by its
+    /// [`len`](`ExactSizeIterator::len`), or if that number does not fit in a `u32`.
+    pub fn serialize_array_from_iter<P, I>(self, iter: I) -> Result<SerializeSuccess<O>, GenError>
+    where
+        P: FixedSizedPod,
+        I: IntoIterator<Item = P>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let mut arr_serializer = self.serialize_array(
+            iter.len()
+                .try_into()
+                .expect("Array length does not fit in a u32"),
+        )?;
+
+        for element in iter {
+            arr_serializer.serialize_element(&element)?;
+        }
+
+        arr_serializer.end()
+    }
+
     /// Begin serializing a `Struct` pod.
     pub fn serialize_struct(mut self) -> Result<StructPodSerializer<O>, GenError> {
         let header_position = self
@@ -403,6 +525,24 @@ impl<O: Write + Seek> PodSerializer<O> {
         })
     }
 
+    /// Serialize a `Struct` pod containing an `Id` pod with the value of `tag`, followed by `payload`.
+    ///
+    /// This is a convenient way of serializing the variants of an enum, using the tag to tell them apart.
+    ///
+    /// This crate does not provide a `#[derive(PodSerialize)]` macro, as that would require a separate
+    /// procedural macro crate. This helper reduces a manual implementation for an enum to one line per variant,
+    /// see the examples of [`PodSerialize`].
+    pub fn serialize_tagged<P: PodSerialize + ?Sized>(
+        self,
+        tag: Id,
+        payload: &P,
+    ) -> Result<SerializeSuccess<O>, GenError> {
+        let mut struct_serializer = self.serialize_struct()?;
+        struct_serializer.serialize_field(&tag)?;
+        struct_serializer.serialize_field(payload)?;
+        struct_serializer.end()
+    }
+
     /// Begin serializing an `Object` pod.
     pub fn serialize_object(
         mut self,
@@ -474,11 +614,7 @@ impl<O: Write + Seek> PodSerializer<O> {
             self.gen(|out| v.serialize_body(out))?;
         }
 
-        let padding = if len % 8 == 0 {
-            0
-        } else {
-            8 - (len as usize % 8)
-        };
+        let padding = align_up(len) - len;
 
         // Add padding to the pod.
         let pad_bytes = self.gen(PodSerializer::padding(padding))?;
@@ -489,6 +625,14 @@ impl<O: Write + Seek> PodSerializer<O> {
         })
     }
 
+    /// Serialize a pod of type `type_` with `body` as its body.
+    ///
+    /// This does not check that `body` is valid for `type_`,
+    /// and is used to write back pods of types that are not supported.
+    pub fn serialize_raw(self, type_: u32, body: &[u8]) -> Result<SerializeSuccess<O>, GenError> {
+        self.write_pod(body.len(), type_, slice(body))
+    }
+
     /// Serialize a pointer pod.
     pub fn serialize_pointer<T>(
         mut self,
@@ -536,8 +680,11 @@ impl<O: Write + Seek, P: FixedSizedPod> ArrayPodSerializer<O, P> {
     /// Serialize a single element.
     ///
     /// Returns the amount of bytes written for this field.
+    ///
+    /// # Panics
+    /// Panics if the specified amount of elements has already been serialized.
     pub fn serialize_element(&mut self, elem: &P) -> Result<u64, GenError> {
-        if !self.written < self.length {
+        if self.written >= self.length {
             panic!("More elements than specified were serialized into the array POD");
         }
 
@@ -555,13 +702,8 @@ impl<O: Write + Seek, P: FixedSizedPod> ArrayPodSerializer<O, P> {
             "Array POD was not serialized with the specified amount of elements"
         );
 
-        let bytes_written = self.written * P::CanonicalType::SIZE;
-
-        let padding = if bytes_written % 8 == 0 {
-            0
-        } else {
-            8 - (bytes_written as usize % 8)
-        };
+        let bytes_written = (self.written * P::CanonicalType::SIZE) as usize;
+        let padding = align_up(bytes_written) - bytes_written;
 
         // Add padding to the pod.
         let pad_bytes = self.serializer.gen(PodSerializer::padding(padding))?;
@@ -610,6 +752,22 @@ impl<O: Write + Seek> StructPodSerializer<O> {
         Ok(success.len)
     }
 
+    /// Serialize a single field of the struct if it is `Some`, skipping it if it is `None`.
+    ///
+    /// This is useful for optional trailing fields, which are simply missing when deserializing
+    /// using [`StructPodDeserializer::deserialize_field`](`super::deserialize::StructPodDeserializer::deserialize_field`).
+    ///
+    /// Returns the amount of bytes written for this field, `0` if it was skipped.
+    pub fn serialize_field_if<P>(&mut self, field: Option<&P>) -> Result<u64, GenError>
+    where
+        P: PodSerialize + ?Sized,
+    {
+        match field {
+            Some(field) => self.serialize_field(field),
+            None => Ok(0),
+        }
+    }
+
     /// Finish serialization of the pod.
     pub fn end(self) -> Result<SerializeSuccess<O>, GenError> {
         let mut serializer = self
@@ -720,10 +878,10 @@ impl<O: Write + Seek> ObjectPodSerializer<O> {
 
         // No padding needed: Last field will already end aligned.
 
-        // Return full length of written pod.
+        // Return full length of written pod: header + object type + object id + properties.
         Ok(SerializeSuccess {
             serializer,
-            len: written as u64,
+            len: written as u64 + 8,
         })
     }
 }
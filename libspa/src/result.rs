@@ -85,6 +85,23 @@ impl SpaResult {
         is_async(self.0)
     }
 
+    /// Returns `true` if the result is an error.
+    pub fn is_error(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// Get the error of the result without consuming it, or `None` if the result is a success.
+    ///
+    /// This is useful when only the failure of a method called for its side effects is of interest,
+    /// e.g. to log it.
+    pub fn error(&self) -> Option<Error> {
+        if self.is_error() {
+            Some(Error::new(-self.0))
+        } else {
+            None
+        }
+    }
+
     /// Convert a [`SpaResult`] into a [`Result`]
     pub fn into_result(self) -> Result<SpaSuccess, Error> {
         if self.0 < 0 {
@@ -135,6 +152,37 @@ impl Error {
 
         Self(Errno(e))
     }
+
+    /// Get the `errno` of the error.
+    pub fn errno(&self) -> Errno {
+        self.0
+    }
+
+    /// Returns `true` if the peer closed the connection, such as a core whose connection
+    /// to the server was lost (`EPIPE`).
+    pub fn is_disconnected(&self) -> bool {
+        self.0 == Errno(libc::EPIPE)
+    }
+
+    /// Returns `true` if the operation is not supported (`ENOTSUP` or `EOPNOTSUPP`).
+    pub fn is_unsupported(&self) -> bool {
+        self.0 == Errno(libc::ENOTSUP) || self.0 == Errno(libc::EOPNOTSUPP)
+    }
+
+    /// Returns `true` if the object or entry does not exist (`ENOENT`).
+    pub fn is_not_found(&self) -> bool {
+        self.0 == Errno(libc::ENOENT)
+    }
+
+    /// Returns `true` if the client lacks the permissions for the operation (`EACCES` or `EPERM`).
+    pub fn is_permission_denied(&self) -> bool {
+        self.0 == Errno(libc::EACCES) || self.0 == Errno(libc::EPERM)
+    }
+
+    /// Returns `true` if the resource is busy (`EBUSY`).
+    pub fn is_busy(&self) -> bool {
+        self.0 == Errno(libc::EBUSY)
+    }
 }
 
 impl std::error::Error for Error {}
@@ -171,9 +219,34 @@ mod tests {
 
         let err = SpaResult::from_c(-libc::EBUSY).into_result().unwrap_err();
         assert_eq!(format!("{}", err), "Device or resource busy",);
+        assert_eq!(err.errno(), Errno(libc::EBUSY));
 
         let res = SpaResult::from_c(-1).into_sync_result();
         assert!(res.is_err());
+
+        assert!(!SpaResult::from_c(0).is_error());
+        assert!(!SpaResult::new_return_async(1).is_error());
+        assert_eq!(SpaResult::from_c(0).error(), None);
+        let res = SpaResult::from_c(-libc::EBUSY);
+        assert!(res.is_error());
+        assert_eq!(res.error(), Some(Error::new(libc::EBUSY)));
+        // The result is not consumed.
+        assert!(res.into_result().is_err());
+    }
+
+    #[test]
+    fn error_predicates() {
+        let err = Error::new(libc::EPIPE);
+        assert!(err.is_disconnected());
+        assert!(!err.is_unsupported());
+
+        assert!(Error::new(libc::ENOTSUP).is_unsupported());
+        assert!(Error::new(libc::EOPNOTSUPP).is_unsupported());
+        assert!(Error::new(libc::ENOENT).is_not_found());
+        assert!(Error::new(libc::EACCES).is_permission_denied());
+        assert!(Error::new(libc::EPERM).is_permission_denied());
+        assert!(Error::new(libc::EBUSY).is_busy());
+        assert!(!Error::new(libc::EBUSY).is_disconnected());
     }
 
     #[test]
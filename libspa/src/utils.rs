@@ -8,14 +8,124 @@ pub use spa_sys::spa_rectangle as Rectangle;
 use crate::pod::CanonicalFixedSizedPod;
 
 /// An enumerated value in a pod
+///
+/// As SPA pods do not have a type for unsigned integers,
+/// `u32` values such as media types or formats have to be wrapped in this type to be serialized.
+///
+/// # Examples
+/// ```rust
+/// use libspa::{pod::serialize::PodSerializer, utils::Id};
+/// use std::io::Cursor;
+///
+/// let format: u32 = spa_sys::spa_audio_format_SPA_AUDIO_FORMAT_S16_LE;
+///
+/// // `PodSerializer::serialize(.., &format)` does not compile, as `u32` can not be serialized.
+/// let (pod, _) = PodSerializer::serialize(Cursor::new(Vec::new()), &Id(format)).unwrap();
+/// ```
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Id(pub u32);
 
+impl From<u32> for Id {
+    /// Wrap a raw value into an [`Id`].
+    ///
+    /// This is handy when building a list of ids, e.g. for an enum choice:
+    /// ```rust
+    /// use libspa::utils::Id;
+    ///
+    /// let ids: Vec<Id> = vec![1, 2, 3].into_iter().map(Id::from).collect();
+    /// assert_eq!(ids, vec![Id(1), Id(2), Id(3)]);
+    ///
+    /// let id: Id = 5.into();
+    /// assert_eq!(id, Id(5));
+    /// ```
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl Id {
+    /// Get the symbolic name of the id within the namespace `table`,
+    /// e.g. `"S16LE"` for the `S16LE` audio format.
+    ///
+    /// Returns `None` if the id is not known in the namespace.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libspa::utils::{Id, SpaType};
+    ///
+    /// let format = Id(spa_sys::spa_audio_format_SPA_AUDIO_FORMAT_S16_LE);
+    /// assert_eq!(format.name_in(SpaType::AudioFormat), Some("S16LE"));
+    /// assert_eq!(Id(u32::MAX).name_in(SpaType::AudioFormat), None);
+    /// ```
+    pub fn name_in(&self, table: SpaType) -> Option<&'static str> {
+        use crate::param::{audio::AudioFormat, video::VideoFormat, MediaSubtype, MediaType};
+
+        match table {
+            SpaType::MediaType => MediaType::from_id(*self).map(|v| v.name()),
+            SpaType::MediaSubtype => MediaSubtype::from_id(*self).map(|v| v.name()),
+            SpaType::AudioFormat => AudioFormat::from_id(*self).map(|v| v.name()),
+            SpaType::VideoFormat => VideoFormat::from_id(*self).map(|v| v.name()),
+        }
+    }
+}
+
+/// A namespace of SPA ids, used to resolve an [`Id`] to its name with [`Id::name_in`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SpaType {
+    /// Media types, see [`MediaType`](`crate::param::MediaType`).
+    MediaType,
+    /// Media subtypes, see [`MediaSubtype`](`crate::param::MediaSubtype`).
+    MediaSubtype,
+    /// Raw audio formats, see [`AudioFormat`](`crate::param::audio::AudioFormat`).
+    AudioFormat,
+    /// Raw video formats, see [`VideoFormat`](`crate::param::video::VideoFormat`).
+    VideoFormat,
+}
+
+impl From<Id> for u32 {
+    fn from(id: Id) -> Self {
+        id.0
+    }
+}
+
+/// An enum whose variants correspond to the values of a SPA enumeration.
+///
+/// This allows converting the raw [`Id`] found in pods into a typed value, e.g.
+/// [`AudioFormat`](`crate::param::audio::AudioFormat`) or [`MediaType`](`crate::param::MediaType`).
+///
+/// # Examples
+/// ```rust
+/// use libspa::{param::audio::AudioFormat, utils::{Id, SpaEnum}};
+///
+/// let id = AudioFormat::S16LE.to_id();
+/// assert_eq!(AudioFormat::from_id(id), Some(AudioFormat::S16LE));
+/// assert_eq!(AudioFormat::from_id(Id(u32::MAX)), None);
+/// ```
+pub trait SpaEnum: Sized {
+    /// Convert an [`Id`] into the corresponding variant,
+    /// returning `None` if the value is not known.
+    fn from_id(id: Id) -> Option<Self>;
+    /// Convert the variant into its [`Id`].
+    fn to_id(&self) -> Id;
+}
+
 /// A file descriptor in a pod
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(transparent)]
 pub struct Fd(pub i64);
 
+impl From<i64> for Fd {
+    fn from(fd: i64) -> Self {
+        Self(fd)
+    }
+}
+
+impl From<Fd> for i64 {
+    fn from(fd: Fd) -> Self {
+        fd.0
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 /// the flags and choice of a choice pod.
 pub struct Choice<T: CanonicalFixedSizedPod>(pub ChoiceFlags, pub ChoiceEnum<T>);
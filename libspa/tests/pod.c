@@ -110,6 +110,18 @@ struct spa_pod *build_test_object(uint8_t *buffer, size_t len)
 									  SPA_PROP_frequency, SPA_POD_Float(440.0f));
 }
 
+struct spa_pod *build_test_format_object(uint8_t *buffer, size_t len)
+{
+	struct spa_pod_builder b = SPA_POD_BUILDER_INIT(buffer, len);
+
+	return spa_pod_builder_add_object(&b,
+									  SPA_TYPE_OBJECT_Format, SPA_PARAM_EnumFormat,
+									  SPA_FORMAT_mediaType, SPA_POD_Id(SPA_MEDIA_TYPE_audio),
+									  SPA_FORMAT_mediaSubtype, SPA_POD_Id(SPA_MEDIA_SUBTYPE_raw),
+									  SPA_FORMAT_AUDIO_format, SPA_POD_CHOICE_ENUM_Id(3, SPA_AUDIO_FORMAT_S16_LE, SPA_AUDIO_FORMAT_S16_LE, SPA_AUDIO_FORMAT_F32_LE),
+									  SPA_FORMAT_AUDIO_rate, SPA_POD_CHOICE_RANGE_Int(48000, 44100, 96000));
+}
+
 struct spa_pod *build_choice_i32(uint8_t *buffer, size_t len, uint32_t choice_type, uint32_t flags, uint32_t n_elems, uint32_t *elems)
 {
 	struct spa_pod_builder b = SPA_POD_BUILDER_INIT(buffer, len);
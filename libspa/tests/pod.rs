@@ -5,14 +5,15 @@ use libspa::{
             DeserializeError, DeserializeSuccess, ObjectPodDeserializer, PodDeserialize,
             StructPodDeserializer, Visitor,
         },
-        serialize::{PodSerialize, PodSerializer, SerializeSuccess},
+        serialize::{PodSerialize, PodSerializeError, PodSerializer, SerializeSuccess},
         CanonicalFixedSizedPod, ChoiceValue, Object, Property, PropertyFlags, Value, ValueArray,
     },
     utils::{Choice, ChoiceEnum, ChoiceFlags, Fd, Fraction, Id, Rectangle},
 };
 use std::{
+    borrow::Cow,
     ffi::{c_void, CString},
-    io::Cursor,
+    io::{Cursor, Write},
     ptr,
 };
 
@@ -51,6 +52,7 @@ pub mod c {
         ) -> *const spa_pod;
         pub fn build_fd(buffer: *mut u8, len: usize, fd: i64) -> i32;
         pub fn build_test_object(buffer: *mut u8, len: usize) -> *const spa_pod;
+        pub fn build_test_format_object(buffer: *mut u8, len: usize) -> *const spa_pod;
         pub fn build_choice_i32(
             buffer: *mut u8,
             len: usize,
@@ -396,6 +398,50 @@ fn string() {
     );
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn string_utf8() {
+    // Multi-byte characters, so that the length in bytes differs from the number of chars.
+    let string = "héllo wörld ♪";
+    assert_eq!(string.len(), 17);
+
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), string)
+        .unwrap()
+        .0
+        .into_inner();
+    let vec_rs_val: Vec<u8> =
+        PodSerializer::serialize(Cursor::new(Vec::new()), &Value::String(string.to_owned()))
+            .unwrap()
+            .0
+            .into_inner();
+    // 8 bytes header, 17 bytes string, 1 byte nul terminator, 6 bytes padding.
+    let mut vec_c: Vec<u8> = vec![0; 32];
+    let c_string = CString::new(string).unwrap();
+    assert_eq!(
+        unsafe {
+            c::build_string(
+                vec_c.as_mut_ptr(),
+                vec_c.len(),
+                c_string.as_bytes_with_nul().as_ptr(),
+            )
+        },
+        0
+    );
+    assert_eq!(vec_rs, vec_c);
+    assert_eq!(vec_rs_val, vec_c);
+    assert_eq!(Value::String(string.to_owned()).serialized_size(), 32);
+
+    assert_eq!(
+        PodDeserializer::deserialize_from(&vec_rs),
+        Ok((&[] as &[u8], string))
+    );
+
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&vec_rs),
+        Ok((&[] as &[u8], Value::String(string.to_string())))
+    );
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn string_no_padding() {
@@ -526,6 +572,12 @@ fn bytes() {
         Ok((&[] as &[u8], Vec::from(bytes as &[u8])))
     );
 
+    // Deserializing into a borrowed `Cow`.
+    assert_eq!(
+        PodDeserializer::deserialize_from(&vec_rs),
+        Ok((&[] as &[u8], Cow::Borrowed(bytes as &[u8])))
+    );
+
     assert_eq!(
         PodDeserializer::deserialize_any_from(&vec_rs),
         Ok((&[] as &[u8], Value::Bytes(Vec::from(bytes as &[u8]))))
@@ -724,6 +776,37 @@ fn array_i32() {
     );
 }
 
+#[test]
+fn array_from_iter() {
+    /// Channel positions computed while serializing, without collecting them first.
+    struct Positions(u32);
+
+    impl PodSerialize for Positions {
+        fn serialize<O: std::io::Write + std::io::Seek>(
+            &self,
+            serializer: PodSerializer<O>,
+        ) -> Result<SerializeSuccess<O>, cookie_factory::GenError> {
+            serializer.serialize_array_from_iter((0..self.0).map(|i| Id(i + 1)))
+        }
+    }
+
+    let (vec_rs, len) = PodSerializer::serialize(Cursor::new(Vec::new()), &Positions(3)).unwrap();
+    let vec_rs = vec_rs.into_inner();
+    let vec_collected: Vec<u8> =
+        PodSerializer::serialize(Cursor::new(Vec::new()), &[Id(1), Id(2), Id(3)][..])
+            .unwrap()
+            .0
+            .into_inner();
+    assert_eq!(vec_rs, vec_collected);
+    assert_eq!(len as usize, vec_rs.len());
+
+    let (_, empty) = PodSerializer::serialize(Cursor::new(Vec::new()), &Positions(0)).unwrap();
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&empty.into_inner()),
+        Ok((&[] as &[u8], Value::ValueArray(ValueArray::Id(Vec::new()))))
+    );
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn array_bool() {
@@ -1452,6 +1535,228 @@ fn object() {
     assert_eq!(vec_rs, vec_c);
 }
 
+#[test]
+#[cfg_attr(miri, ignore)]
+fn object_choice_property() {
+    let mut vec_c: Vec<u8> = vec![0; 160];
+    assert_ne!(
+        unsafe { c::build_test_format_object(vec_c.as_mut_ptr(), vec_c.len()) },
+        std::ptr::null()
+    );
+
+    let formats = Choice(
+        ChoiceFlags::empty(),
+        ChoiceEnum::Enum {
+            default: Id(spa_sys::spa_audio_format_SPA_AUDIO_FORMAT_S16_LE),
+            alternatives: vec![
+                Id(spa_sys::spa_audio_format_SPA_AUDIO_FORMAT_S16_LE),
+                Id(spa_sys::spa_audio_format_SPA_AUDIO_FORMAT_F32_LE),
+            ],
+        },
+    );
+    let rates = Choice(
+        ChoiceFlags::empty(),
+        ChoiceEnum::Range {
+            default: 48000,
+            min: 44100,
+            max: 96000,
+        },
+    );
+
+    #[derive(Debug, PartialEq)]
+    struct MyFormat {
+        formats: Choice<Id>,
+        rates: Choice<i32>,
+    }
+
+    impl<'de> PodDeserialize<'de> for MyFormat {
+        fn deserialize(
+            deserializer: PodDeserializer<'de>,
+        ) -> Result<(Self, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>>
+        where
+            Self: Sized,
+        {
+            struct FormatVisitor;
+
+            impl<'de> Visitor<'de> for FormatVisitor {
+                type Value = MyFormat;
+                type ArrayElem = std::convert::Infallible;
+
+                fn visit_object(
+                    &self,
+                    object_deserializer: &mut ObjectPodDeserializer<'de>,
+                ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
+                    object_deserializer
+                        .deserialize_property_key::<Id>(spa_sys::spa_format_SPA_FORMAT_mediaType)?;
+                    object_deserializer.deserialize_property_key::<Id>(
+                        spa_sys::spa_format_SPA_FORMAT_mediaSubtype,
+                    )?;
+                    let (formats, _flags) = object_deserializer
+                        .deserialize_property_key::<Choice<Id>>(
+                            spa_sys::spa_format_SPA_FORMAT_AUDIO_format,
+                        )?;
+                    let (rates, _flags) = object_deserializer
+                        .deserialize_property_key::<Choice<i32>>(
+                            spa_sys::spa_format_SPA_FORMAT_AUDIO_rate,
+                        )?;
+
+                    Ok(MyFormat { formats, rates })
+                }
+            }
+
+            deserializer.deserialize_object(FormatVisitor)
+        }
+    }
+
+    assert_eq!(
+        PodDeserializer::deserialize_from::<MyFormat>(&vec_c),
+        Ok((
+            &[] as &[u8],
+            MyFormat {
+                formats: formats.clone(),
+                rates: rates.clone(),
+            }
+        ))
+    );
+
+    let value = Value::Object(Object {
+        type_: spa_sys::SPA_TYPE_OBJECT_Format,
+        id: spa_sys::spa_param_type_SPA_PARAM_EnumFormat,
+        properties: vec![
+            Property {
+                key: spa_sys::spa_format_SPA_FORMAT_mediaType,
+                flags: PropertyFlags::empty(),
+                value: Value::Id(Id(spa_sys::spa_media_type_SPA_MEDIA_TYPE_audio)),
+            },
+            Property {
+                key: spa_sys::spa_format_SPA_FORMAT_mediaSubtype,
+                flags: PropertyFlags::empty(),
+                value: Value::Id(Id(spa_sys::spa_media_subtype_SPA_MEDIA_SUBTYPE_raw)),
+            },
+            Property {
+                key: spa_sys::spa_format_SPA_FORMAT_AUDIO_format,
+                flags: PropertyFlags::empty(),
+                value: Value::Choice(ChoiceValue::Id(formats)),
+            },
+            Property {
+                key: spa_sys::spa_format_SPA_FORMAT_AUDIO_rate,
+                flags: PropertyFlags::empty(),
+                value: Value::Choice(ChoiceValue::Int(rates)),
+            },
+        ],
+    });
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&vec_c),
+        Ok((&[] as &[u8], value.clone()))
+    );
+
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+        .unwrap()
+        .0
+        .into_inner();
+    assert_eq!(vec_rs, vec_c);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn object_any_property() {
+    let mut vec_c: Vec<u8> = vec![0; 64];
+    unsafe { c::build_test_object(vec_c.as_mut_ptr(), vec_c.len()) };
+
+    struct AllProps(Vec<(u32, PropertyFlags, Value)>);
+
+    impl<'de> PodDeserialize<'de> for AllProps {
+        fn deserialize(
+            deserializer: PodDeserializer<'de>,
+        ) -> Result<(Self, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>>
+        where
+            Self: Sized,
+        {
+            struct AllPropsVisitor;
+
+            impl<'de> Visitor<'de> for AllPropsVisitor {
+                type Value = AllProps;
+                type ArrayElem = std::convert::Infallible;
+
+                fn visit_object(
+                    &self,
+                    object_deserializer: &mut ObjectPodDeserializer<'de>,
+                ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
+                    let mut props = Vec::new();
+                    while let Some(prop) = object_deserializer.deserialize_any_property()? {
+                        props.push(prop);
+                    }
+
+                    Ok(AllProps(props))
+                }
+            }
+
+            deserializer.deserialize_object(AllPropsVisitor)
+        }
+    }
+
+    let (_, props) = PodDeserializer::deserialize_from::<AllProps>(&vec_c).unwrap();
+    assert_eq!(
+        props.0,
+        vec![
+            (
+                spa_sys::spa_prop_SPA_PROP_device,
+                PropertyFlags::empty(),
+                Value::String("hw:0".into())
+            ),
+            (
+                spa_sys::spa_prop_SPA_PROP_frequency,
+                PropertyFlags::empty(),
+                Value::Float(440.0)
+            ),
+        ]
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn iter_object() {
+    let mut vec_c: Vec<u8> = vec![0; 64];
+    unsafe { c::build_test_object(vec_c.as_mut_ptr(), vec_c.len()) };
+
+    let iter = PodDeserializer::iter_object(&vec_c).unwrap();
+    assert_eq!(iter.object_type(), spa_sys::SPA_TYPE_OBJECT_Props);
+    assert_eq!(iter.object_id(), spa_sys::spa_param_type_SPA_PARAM_Props);
+
+    let props: Result<Vec<_>, _> = iter.collect();
+    assert_eq!(
+        props,
+        Ok(vec![
+            (
+                spa_sys::spa_prop_SPA_PROP_device,
+                PropertyFlags::empty(),
+                Value::String("hw:0".into())
+            ),
+            (
+                spa_sys::spa_prop_SPA_PROP_frequency,
+                PropertyFlags::empty(),
+                Value::Float(440.0)
+            ),
+        ])
+    );
+
+    // Only the properties up to the requested one are deserialized.
+    let device = PodDeserializer::iter_object(&vec_c)
+        .unwrap()
+        .find_map(|prop| match prop {
+            Ok((spa_sys::spa_prop_SPA_PROP_device, _, value)) => Some(value),
+            _ => None,
+        });
+    assert_eq!(device, Some(Value::String("hw:0".into())));
+
+    // Not an object pod.
+    let vec_int: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &42i32)
+        .unwrap()
+        .0
+        .into_inner();
+    assert!(PodDeserializer::iter_object(&vec_int).is_err());
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn choice_range_f32() {
@@ -2103,6 +2408,176 @@ fn choice_extra_values() {
     );
 }
 
+#[test]
+fn value_from() {
+    assert_eq!(Value::from(true), Value::Bool(true));
+    assert_eq!(Value::from(Id(3)), Value::Id(Id(3)));
+    assert_eq!(Value::from(5i32), Value::Int(5));
+    assert_eq!(Value::from(5i64), Value::Long(5));
+    assert_eq!(Value::from(0.5f32), Value::Float(0.5));
+    assert_eq!(Value::from("hw:0"), Value::String("hw:0".into()));
+    assert_eq!(Value::from(vec![1u8, 2]), Value::Bytes(vec![1, 2]));
+    assert_eq!(
+        Value::from(vec![1, 2, 3]),
+        Value::ValueArray(ValueArray::Int(vec![1, 2, 3]))
+    );
+    assert_eq!(
+        Value::from(vec![0.5f32, 1.0]),
+        Value::ValueArray(ValueArray::Float(vec![0.5, 1.0]))
+    );
+    assert_eq!(
+        Value::from(vec![Value::from(1), Value::from("foo")]),
+        Value::Struct(vec![Value::Int(1), Value::String("foo".into())])
+    );
+
+    let choice = Choice(
+        ChoiceFlags::empty(),
+        ChoiceEnum::Range {
+            default: 1.0f32,
+            min: 0.0,
+            max: 2.0,
+        },
+    );
+    assert_eq!(
+        Value::from(choice.clone()),
+        Value::Choice(ChoiceValue::Float(choice))
+    );
+}
+
+#[test]
+fn deserialize_from_with_fds() {
+    let value = Value::Struct(vec![
+        Value::Int(1),
+        Value::Fd(Fd(1)),
+        Value::ValueArray(ValueArray::Fd(vec![Fd(0), Fd(1)])),
+        Value::Choice(ChoiceValue::Fd(Choice(
+            ChoiceFlags::empty(),
+            ChoiceEnum::Enum {
+                default: Fd(0),
+                alternatives: vec![Fd(1)],
+            },
+        ))),
+    ]);
+    let pod: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+        .unwrap()
+        .0
+        .into_inner();
+
+    assert_eq!(
+        PodDeserializer::deserialize_from_with_fds(&pod, &[10, 11]),
+        Ok((
+            &[] as &[u8],
+            Value::Struct(vec![
+                Value::Int(1),
+                Value::Fd(Fd(11)),
+                Value::ValueArray(ValueArray::Fd(vec![Fd(10), Fd(11)])),
+                Value::Choice(ChoiceValue::Fd(Choice(
+                    ChoiceFlags::empty(),
+                    ChoiceEnum::Enum {
+                        default: Fd(10),
+                        alternatives: vec![Fd(11)],
+                    },
+                ))),
+            ])
+        ))
+    );
+
+    assert_eq!(
+        PodDeserializer::deserialize_from_with_fds(&pod, &[10]),
+        Err(DeserializeError::InvalidFd(1))
+    );
+}
+
+#[test]
+fn serialize_tagged() {
+    enum Shape {
+        Circle(f32),
+        Label(String),
+    }
+
+    impl PodSerialize for Shape {
+        fn serialize<O: std::io::Write + std::io::Seek>(
+            &self,
+            serializer: PodSerializer<O>,
+        ) -> Result<SerializeSuccess<O>, cookie_factory::GenError> {
+            match self {
+                Shape::Circle(radius) => serializer.serialize_tagged(Id(0), radius),
+                Shape::Label(text) => serializer.serialize_tagged(Id(1), text.as_str()),
+            }
+        }
+    }
+
+    for (shape, expected) in [
+        (
+            Shape::Circle(2.5),
+            Value::Struct(vec![Value::Id(Id(0)), Value::Float(2.5)]),
+        ),
+        (
+            Shape::Label("foo".into()),
+            Value::Struct(vec![Value::Id(Id(1)), Value::String("foo".into())]),
+        ),
+    ] {
+        let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &shape)
+            .unwrap()
+            .0
+            .into_inner();
+        assert_eq!(
+            PodDeserializer::deserialize_any_from(&vec_rs),
+            Ok((&[] as &[u8], expected))
+        );
+    }
+}
+
+/// Build a raw choice pod by hand, as the C builder does not allow choices of unsupported types.
+fn raw_choice(choice_type: u32, child_size: u32, child_type: u32, values: &[u32]) -> Vec<u8> {
+    let body_len = 16 + values.len() as u32 * 4;
+    let mut words = vec![
+        body_len,
+        spa_sys::SPA_TYPE_Choice,
+        choice_type,
+        0,
+        child_size,
+        child_type,
+    ];
+    words.extend_from_slice(values);
+    if words.len() % 2 != 0 {
+        // padding
+        words.push(0);
+    }
+
+    words.iter().flat_map(|word| word.to_ne_bytes()).collect()
+}
+
+#[test]
+fn choice_unsupported_element_type() {
+    let bool_choice = raw_choice(
+        spa_sys::spa_choice_type_SPA_CHOICE_Enum,
+        4,
+        spa_sys::SPA_TYPE_Bool,
+        &[1, 0, 1],
+    );
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&bool_choice),
+        Err(DeserializeError::UnsupportedChoiceElementType(
+            spa_sys::SPA_TYPE_Bool
+        ))
+    );
+
+    // Zero sized values must not cause a division by zero.
+    let none_choice = raw_choice(
+        spa_sys::spa_choice_type_SPA_CHOICE_None,
+        0,
+        spa_sys::SPA_TYPE_None,
+        &[],
+    );
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&none_choice),
+        Err(DeserializeError::UnsupportedChoiceElementType(
+            spa_sys::SPA_TYPE_None
+        ))
+    );
+}
+
 #[test]
 #[cfg_attr(miri, ignore)]
 fn pointer() {
@@ -2148,3 +2623,591 @@ fn pointer() {
         ))
     );
 }
+
+#[test]
+fn deserialize_from_len() {
+    // Two pods following each other in the same buffer.
+    let mut buffer = Cursor::new(Vec::new());
+    PodSerializer::serialize(&mut buffer, &765i32).unwrap();
+    PodSerializer::serialize(&mut buffer, "foo").unwrap();
+    let vec_rs = buffer.into_inner();
+
+    let (len, int) = PodDeserializer::deserialize_from_len::<i32>(&vec_rs).unwrap();
+    assert_eq!(len, 16);
+    assert_eq!(int, 765);
+
+    let (len, string) = PodDeserializer::deserialize_from_len::<String>(&vec_rs[16..]).unwrap();
+    assert_eq!(len, 16);
+    assert_eq!(string, "foo");
+}
+
+#[test]
+fn pod_size() {
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &765i32)
+        .unwrap()
+        .0
+        .into_inner();
+
+    assert_eq!(libspa::pod::pod_size(&vec_rs), Some(16));
+    assert_eq!(libspa::pod::pod_size(&vec_rs[..8]), None);
+    assert_eq!(libspa::pod::pod_size(&vec_rs[..4]), None);
+    assert_eq!(libspa::pod::pod_size(&[]), None);
+}
+
+#[test]
+fn serialized_size() {
+    let values = vec![
+        Value::None,
+        Value::Bool(true),
+        Value::Int(1),
+        Value::Long(1),
+        Value::String("".into()),
+        Value::String("1234567".into()),
+        Value::String("12345678".into()),
+        Value::Bytes(vec![1, 2, 3]),
+        Value::Rectangle(Rectangle {
+            width: 1,
+            height: 2,
+        }),
+        Value::ValueArray(ValueArray::Int(vec![1, 2, 3])),
+        Value::ValueArray(ValueArray::None(vec![])),
+        Value::Struct(vec![Value::Int(1), Value::String("foo".into())]),
+        Value::Object(Object {
+            type_: spa_sys::SPA_TYPE_OBJECT_Props,
+            id: spa_sys::spa_param_type_SPA_PARAM_Props,
+            properties: vec![
+                Property {
+                    key: spa_sys::spa_prop_SPA_PROP_device,
+                    flags: PropertyFlags::empty(),
+                    value: Value::String("hw:0".into()),
+                },
+                Property {
+                    key: spa_sys::spa_prop_SPA_PROP_frequency,
+                    flags: PropertyFlags::empty(),
+                    value: Value::Float(440.0),
+                },
+            ],
+        }),
+        Value::Choice(ChoiceValue::Int(Choice(
+            ChoiceFlags::empty(),
+            ChoiceEnum::Enum {
+                default: 1,
+                alternatives: vec![1, 2, 3],
+            },
+        ))),
+        Value::Pointer(0, ptr::null()),
+    ];
+
+    for value in values {
+        let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+            .unwrap()
+            .0
+            .into_inner();
+        assert_eq!(value.serialized_size(), vec_rs.len(), "{:?}", value);
+    }
+}
+
+#[test]
+fn array_fixed_size() {
+    let array: [i32; 3] = [1, 2, 3];
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &array[..])
+        .unwrap()
+        .0
+        .into_inner();
+
+    assert_eq!(
+        PodDeserializer::deserialize_from(&vec_rs),
+        Ok((&[] as &[u8], array))
+    );
+    assert_eq!(
+        PodDeserializer::deserialize_from::<[i32; 2]>(&vec_rs),
+        Err(DeserializeError::WrongArrayLength(3))
+    );
+}
+
+#[test]
+fn spa_enum_id() {
+    use libspa::{param::audio::AudioFormat, utils::SpaEnum};
+
+    let value = Value::Id(AudioFormat::S16LE.to_id());
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+        .unwrap()
+        .0
+        .into_inner();
+
+    let (_, value) = PodDeserializer::deserialize_any_from(&vec_rs).unwrap();
+    let format = match value {
+        Value::Id(id) => AudioFormat::from_id(id),
+        _ => panic!("Expected an id"),
+    };
+    assert_eq!(format, Some(AudioFormat::S16LE));
+    assert_eq!(
+        AudioFormat::S16LE.to_id(),
+        Id(spa_sys::spa_audio_format_SPA_AUDIO_FORMAT_S16_LE)
+    );
+}
+
+#[test]
+fn duration() {
+    use std::time::Duration;
+
+    let duration = Duration::from_millis(1500);
+
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &duration)
+        .unwrap()
+        .0
+        .into_inner();
+    let vec_long: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &1_500_000_000i64)
+        .unwrap()
+        .0
+        .into_inner();
+    assert_eq!(vec_rs, vec_long);
+
+    assert_eq!(
+        PodDeserializer::deserialize_from(&vec_rs),
+        Ok((&[] as &[u8], duration))
+    );
+
+    // Negative amounts of nanoseconds can not be represented by a duration.
+    let vec_negative: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &-1i64)
+        .unwrap()
+        .0
+        .into_inner();
+    assert_eq!(
+        PodDeserializer::deserialize_from(&vec_negative),
+        Ok((&[] as &[u8], Duration::ZERO))
+    );
+}
+
+/// Deserializes the elements of an `Int` array pod one by one,
+/// attempting to deserialize `extra` elements more than the array contains.
+struct ArrayElements<const EXTRA: u32>(Vec<i32>);
+
+impl<'de, const EXTRA: u32> PodDeserialize<'de> for ArrayElements<EXTRA> {
+    fn deserialize(
+        deserializer: PodDeserializer<'de>,
+    ) -> Result<(Self, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>>
+    where
+        Self: Sized,
+    {
+        let (mut array_deserializer, length) = deserializer.new_array_deserializer::<i32>()?;
+        let mut elements = Vec::new();
+        for _ in 0..length + EXTRA {
+            elements.push(array_deserializer.deserialize_element()?);
+        }
+        let success = array_deserializer.end()?;
+
+        Ok((ArrayElements(elements), success))
+    }
+}
+
+#[test]
+fn array_deserialize_elements() {
+    let vec_rs: Vec<u8> = PodSerializer::serialize(
+        Cursor::new(Vec::new()),
+        &Value::ValueArray(ValueArray::Int(vec![1, 2, 3])),
+    )
+    .unwrap()
+    .0
+    .into_inner();
+
+    let (_, elements): (_, ArrayElements<0>) = PodDeserializer::deserialize_from(&vec_rs).unwrap();
+    assert_eq!(elements.0, vec![1, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "No elements left in the pod to deserialize")]
+fn array_deserialize_too_many_elements() {
+    let vec_rs: Vec<u8> = PodSerializer::serialize(
+        Cursor::new(Vec::new()),
+        &Value::ValueArray(ValueArray::Int(vec![1, 2, 3])),
+    )
+    .unwrap()
+    .0
+    .into_inner();
+
+    let _: Result<(_, ArrayElements<1>), _> = PodDeserializer::deserialize_from(&vec_rs);
+}
+
+/// Serializes an `Int` array pod declared to have `length` elements.
+struct DeclaredLengthArray {
+    length: u32,
+    elements: Vec<i32>,
+}
+
+impl PodSerialize for DeclaredLengthArray {
+    fn serialize<O: std::io::Write + std::io::Seek>(
+        &self,
+        serializer: PodSerializer<O>,
+    ) -> Result<SerializeSuccess<O>, cookie_factory::GenError> {
+        let mut array_serializer = serializer.serialize_array(self.length)?;
+        for element in &self.elements {
+            array_serializer.serialize_element(element)?;
+        }
+        array_serializer.end()
+    }
+}
+
+#[test]
+#[should_panic(expected = "More elements than specified were serialized into the array POD")]
+fn array_serialize_too_many_elements() {
+    let _ = PodSerializer::serialize(
+        Cursor::new(Vec::new()),
+        &DeclaredLengthArray {
+            length: 2,
+            elements: vec![1, 2, 3],
+        },
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn array_empty_in_struct() {
+    // Build an empty `None` array using the C builder,
+    // and check that it is entirely consumed when followed by another pod.
+    let mut vec_c: Vec<u8> = vec![0; 16];
+    unsafe {
+        c::build_array(
+            vec_c.as_mut_ptr(),
+            vec_c.len(),
+            0,
+            spa_sys::SPA_TYPE_None,
+            0,
+            ptr::null(),
+        )
+    };
+    let vec_int: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &42i32)
+        .unwrap()
+        .0
+        .into_inner();
+    let input: Vec<u8> = vec_c.iter().chain(vec_int.iter()).copied().collect();
+
+    let (rest, array): (_, Vec<()>) = PodDeserializer::deserialize_from(&input).unwrap();
+    assert!(array.is_empty());
+    assert_eq!(rest, vec_int.as_slice());
+    assert_eq!(
+        PodDeserializer::deserialize_from(rest),
+        Ok((&[] as &[u8], 42i32))
+    );
+
+    let value = Value::Struct(vec![
+        Value::ValueArray(ValueArray::None(Vec::new())),
+        Value::Int(42),
+    ]);
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+        .unwrap()
+        .0
+        .into_inner();
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&vec_rs),
+        Ok((&[] as &[u8], value))
+    );
+}
+
+#[test]
+fn unwrap_choice() {
+    let fixed = ChoiceValue::Fraction(Choice(
+        ChoiceFlags::empty(),
+        ChoiceEnum::None(Fraction { num: 30, denom: 1 }),
+    ));
+    assert_eq!(
+        fixed.fixed(),
+        Some(Value::Fraction(Fraction { num: 30, denom: 1 }))
+    );
+    assert_eq!(
+        Value::Choice(fixed).unwrap_choice(),
+        Value::Fraction(Fraction { num: 30, denom: 1 })
+    );
+
+    let range = ChoiceValue::Int(Choice(
+        ChoiceFlags::empty(),
+        ChoiceEnum::Range {
+            default: 2,
+            min: 1,
+            max: 8,
+        },
+    ));
+    assert_eq!(range.fixed(), None);
+    assert_eq!(
+        Value::Choice(range.clone()).unwrap_choice(),
+        Value::Choice(range)
+    );
+}
+
+#[test]
+fn deserialize_keep_unknown() {
+    const UNKNOWN_TYPE: u32 = 0x1234;
+
+    // A struct holding an int, a pod of an unknown type and a string.
+    let words: &[u32] = &[
+        48,
+        spa_sys::SPA_TYPE_Struct,
+        4,
+        spa_sys::SPA_TYPE_Int,
+        42,
+        0,
+        6,
+        UNKNOWN_TYPE,
+        u32::from_ne_bytes([1, 2, 3, 4]),
+        u32::from_ne_bytes([5, 6, 0, 0]),
+        3,
+        spa_sys::SPA_TYPE_String,
+        u32::from_ne_bytes(*b"ab\0\0"),
+        0,
+    ];
+    let input: Vec<u8> = words.iter().flat_map(|word| word.to_ne_bytes()).collect();
+
+    assert_eq!(
+        PodDeserializer::deserialize_any_from(&input),
+        Err(DeserializeError::InvalidType)
+    );
+
+    let (remaining, value) = PodDeserializer::deserialize_any_from_keep_unknown(&input).unwrap();
+    assert!(remaining.is_empty());
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::Int(42),
+            Value::Unknown {
+                type_: UNKNOWN_TYPE,
+                body: vec![1, 2, 3, 4, 5, 6],
+            },
+            Value::String("ab".to_string()),
+        ])
+    );
+
+    // The unknown pod is serialized back as is.
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+        .unwrap()
+        .0
+        .into_inner();
+    assert_eq!(vec_rs, input);
+    assert_eq!(value.serialized_size(), input.len());
+}
+
+#[test]
+fn serialize_len() {
+    let value = Value::Struct(vec![
+        Value::Int(1),
+        Value::String("abc".to_string()),
+        Value::Object(Object {
+            type_: spa_sys::SPA_TYPE_OBJECT_Props,
+            id: spa_sys::spa_param_type_SPA_PARAM_Props,
+            properties: vec![Property {
+                key: spa_sys::spa_prop_SPA_PROP_volume,
+                flags: PropertyFlags::empty(),
+                value: Value::Object(Object {
+                    type_: spa_sys::SPA_TYPE_OBJECT_Props,
+                    id: spa_sys::spa_param_type_SPA_PARAM_Props,
+                    properties: vec![Property {
+                        key: spa_sys::spa_prop_SPA_PROP_mute,
+                        flags: PropertyFlags::empty(),
+                        value: Value::Bool(true),
+                    }],
+                }),
+            }],
+        }),
+        Value::ValueArray(ValueArray::Int(vec![1, 2, 3])),
+    ]);
+
+    // Write the pod after a length prefix, like in a framed message.
+    let mut cursor = Cursor::new(Vec::new());
+    cursor.write_all(&[0; 8]).unwrap();
+    let (cursor, len) = PodSerializer::serialize(cursor, &value).unwrap();
+    let message = cursor.into_inner();
+
+    assert_eq!(len as usize, value.serialized_size());
+    assert_eq!(len as usize, message.len() - 8);
+
+    let (remaining, deserialized) =
+        PodDeserializer::deserialize_any_from(&message[8..8 + len as usize]).unwrap();
+    assert!(remaining.is_empty());
+    assert_eq!(deserialized, value);
+}
+
+#[test]
+fn value_len() {
+    let array = Value::ValueArray(ValueArray::Int(vec![44100, 48000, 96000]));
+    assert_eq!(array.len(), Some(3));
+    assert_eq!(array.is_empty(), Some(false));
+
+    let empty_struct = Value::Struct(Vec::new());
+    assert_eq!(empty_struct.len(), Some(0));
+    assert_eq!(empty_struct.is_empty(), Some(true));
+
+    let object = Value::Object(Object {
+        type_: spa_sys::SPA_TYPE_OBJECT_Props,
+        id: spa_sys::spa_param_type_SPA_PARAM_Props,
+        properties: vec![Property {
+            key: spa_sys::spa_prop_SPA_PROP_volume,
+            flags: PropertyFlags::empty(),
+            value: Value::Float(0.5),
+        }],
+    });
+    assert_eq!(object.len(), Some(1));
+
+    assert_eq!(Value::Int(1).len(), None);
+    assert_eq!(Value::String("abc".to_string()).is_empty(), None);
+}
+
+#[test]
+fn value_semantic_eq() {
+    let none_choice = |rate| {
+        Value::Choice(ChoiceValue::Int(Choice(
+            ChoiceFlags::empty(),
+            ChoiceEnum::None(rate),
+        )))
+    };
+    let format = |rate| {
+        Value::Object(Object {
+            type_: spa_sys::SPA_TYPE_OBJECT_Format,
+            id: spa_sys::spa_param_type_SPA_PARAM_Format,
+            properties: vec![Property {
+                key: spa_sys::spa_format_SPA_FORMAT_AUDIO_rate,
+                flags: PropertyFlags::empty(),
+                value: rate,
+            }],
+        })
+    };
+
+    assert!(format(Value::Int(48000)).semantic_eq(&format(none_choice(48000))));
+    assert!(format(none_choice(48000)).semantic_eq(&format(Value::Int(48000))));
+    assert!(!format(Value::Int(44100)).semantic_eq(&format(none_choice(48000))));
+
+    let fields = Value::Struct(vec![none_choice(1), Value::String("abc".to_string())]);
+    assert!(fields.semantic_eq(&Value::Struct(vec![
+        Value::Int(1),
+        Value::String("abc".to_string())
+    ])));
+    assert!(!fields.semantic_eq(&Value::Struct(vec![Value::Int(1)])));
+
+    // Choices offering multiple values are only equal to the same choice.
+    let range = Value::Choice(ChoiceValue::Int(Choice(
+        ChoiceFlags::empty(),
+        ChoiceEnum::Range {
+            default: 1,
+            min: 0,
+            max: 2,
+        },
+    )));
+    assert!(range.semantic_eq(&range));
+    assert!(!range.semantic_eq(&Value::Int(1)));
+}
+
+#[test]
+fn serialize_to_writer() {
+    let value = Value::Struct(vec![Value::Int(1), Value::String("abc".to_string())]);
+    let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &value)
+        .unwrap()
+        .0
+        .into_inner();
+
+    // Write after some existing data, as into a stream.
+    let (written, len) = libspa::pod::serialize_to_writer(vec![0xff; 4], &value).unwrap();
+    assert_eq!(len as usize, vec_rs.len());
+    assert_eq!(&written[..4], &[0xff; 4]);
+    assert_eq!(&written[4..], vec_rs.as_slice());
+}
+
+#[test]
+fn serialize_to_writer_error() {
+    struct BrokenPipe;
+
+    impl Write for BrokenPipe {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let err = libspa::pod::serialize_to_writer(BrokenPipe, &Value::Int(1)).unwrap_err();
+    assert!(matches!(err, PodSerializeError::Other(_)));
+
+    // The error of the writer is the source of the serialization error.
+    let source = std::error::Error::source(&err)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .expect("io error source");
+    assert_eq!(source.kind(), std::io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn struct_optional_field() {
+    #[derive(Debug, PartialEq)]
+    struct Message {
+        version: i32,
+        // Only present in newer versions of the message.
+        name: Option<String>,
+    }
+
+    impl PodSerialize for Message {
+        fn serialize<O: std::io::Write + std::io::Seek>(
+            &self,
+            serializer: PodSerializer<O>,
+        ) -> Result<SerializeSuccess<O>, cookie_factory::GenError> {
+            let mut struct_serializer = serializer.serialize_struct()?;
+
+            struct_serializer.serialize_field(&self.version)?;
+            struct_serializer.serialize_field_if(self.name.as_deref())?;
+
+            struct_serializer.end()
+        }
+    }
+
+    impl<'de> PodDeserialize<'de> for Message {
+        fn deserialize(
+            deserializer: PodDeserializer<'de>,
+        ) -> Result<(Self, DeserializeSuccess<'de>), DeserializeError<&'de [u8]>>
+        where
+            Self: Sized,
+        {
+            struct MessageVisitor;
+
+            impl<'de> Visitor<'de> for MessageVisitor {
+                type Value = Message;
+                type ArrayElem = std::convert::Infallible;
+
+                fn visit_struct(
+                    &self,
+                    struct_deserializer: &mut StructPodDeserializer<'de>,
+                ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
+                    Ok(Message {
+                        version: struct_deserializer
+                            .deserialize_field()?
+                            .expect("Input has too few fields"),
+                        name: struct_deserializer.deserialize_field()?,
+                    })
+                }
+            }
+
+            deserializer.deserialize_struct(MessageVisitor)
+        }
+    }
+
+    for message in [
+        Message {
+            version: 1,
+            name: None,
+        },
+        Message {
+            version: 2,
+            name: Some("foo".to_string()),
+        },
+    ] {
+        let vec_rs: Vec<u8> = PodSerializer::serialize(Cursor::new(Vec::new()), &message)
+            .unwrap()
+            .0
+            .into_inner();
+        let fields = if message.name.is_some() { 2 } else { 1 };
+        assert_eq!(
+            PodDeserializer::deserialize_any_from(&vec_rs).map(|(_, value)| value.len()),
+            Ok(Some(fields))
+        );
+        assert_eq!(
+            PodDeserializer::deserialize_from(&vec_rs),
+            Ok((&[] as &[u8], message))
+        );
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Property-based tests checking that any [`Value`] survives a serialization round-trip.
+
+use libspa::{
+    pod::{
+        deserialize::PodDeserializer, serialize::PodSerializer, CanonicalFixedSizedPod,
+        ChoiceValue, Object, Property, PropertyFlags, Value, ValueArray,
+    },
+    utils::{Choice, ChoiceEnum, ChoiceFlags, Fd, Fraction, Id, Rectangle},
+};
+use quickcheck::{Arbitrary, Gen, QuickCheck, TestResult};
+use std::{ffi::c_void, io::Cursor};
+
+/// Maximum nesting depth of structs, objects and their properties.
+const MAX_DEPTH: usize = 3;
+/// Maximum number of fields of a struct or properties of an object.
+const MAX_CHILDREN: usize = 4;
+
+#[derive(Debug, Clone)]
+struct ArbitraryValue(Value);
+
+impl Arbitrary for ArbitraryValue {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self(value(g, MAX_DEPTH))
+    }
+}
+
+fn float(g: &mut Gen) -> f32 {
+    // NaN is not equal to itself, so the round-tripped value could not be compared.
+    let f = f32::arbitrary(g);
+    if f.is_nan() {
+        0.0
+    } else {
+        f
+    }
+}
+
+fn double(g: &mut Gen) -> f64 {
+    let f = f64::arbitrary(g);
+    if f.is_nan() {
+        0.0
+    } else {
+        f
+    }
+}
+
+fn id(g: &mut Gen) -> Id {
+    Id(u32::arbitrary(g))
+}
+
+fn rectangle(g: &mut Gen) -> Rectangle {
+    Rectangle {
+        width: u32::arbitrary(g),
+        height: u32::arbitrary(g),
+    }
+}
+
+fn fraction(g: &mut Gen) -> Fraction {
+    Fraction {
+        num: u32::arbitrary(g),
+        denom: u32::arbitrary(g),
+    }
+}
+
+fn fd(g: &mut Gen) -> Fd {
+    Fd(i64::arbitrary(g))
+}
+
+fn vec<T>(g: &mut Gen, f: fn(&mut Gen) -> T) -> Vec<T> {
+    let len = usize::arbitrary(g) % (g.size() + 1);
+    (0..len).map(|_| f(g)).collect()
+}
+
+fn choice<T: CanonicalFixedSizedPod>(g: &mut Gen, f: fn(&mut Gen) -> T) -> Choice<T> {
+    let choice = match u8::arbitrary(g) % 5 {
+        0 => ChoiceEnum::None(f(g)),
+        1 => ChoiceEnum::Range {
+            default: f(g),
+            min: f(g),
+            max: f(g),
+        },
+        2 => ChoiceEnum::Step {
+            default: f(g),
+            min: f(g),
+            max: f(g),
+            step: f(g),
+        },
+        3 => ChoiceEnum::Enum {
+            default: f(g),
+            alternatives: vec(g, f),
+        },
+        _ => ChoiceEnum::Flags {
+            default: f(g),
+            flags: vec(g, f),
+        },
+    };
+
+    // No choice flags are defined yet.
+    Choice(ChoiceFlags::empty(), choice)
+}
+
+fn array(g: &mut Gen) -> ValueArray {
+    match u8::arbitrary(g) % 10 {
+        // Elements of `None` arrays have no size, so their number can not be stored.
+        0 => ValueArray::None(Vec::new()),
+        1 => ValueArray::Bool(Vec::arbitrary(g)),
+        2 => ValueArray::Id(vec(g, id)),
+        3 => ValueArray::Int(Vec::arbitrary(g)),
+        4 => ValueArray::Long(Vec::arbitrary(g)),
+        5 => ValueArray::Float(vec(g, float)),
+        6 => ValueArray::Double(vec(g, double)),
+        7 => ValueArray::Rectangle(vec(g, rectangle)),
+        8 => ValueArray::Fraction(vec(g, fraction)),
+        _ => ValueArray::Fd(vec(g, fd)),
+    }
+}
+
+fn choice_value(g: &mut Gen) -> ChoiceValue {
+    match u8::arbitrary(g) % 8 {
+        0 => ChoiceValue::Int(choice(g, i32::arbitrary)),
+        1 => ChoiceValue::Long(choice(g, i64::arbitrary)),
+        2 => ChoiceValue::Float(choice(g, float)),
+        3 => ChoiceValue::Double(choice(g, double)),
+        4 => ChoiceValue::Id(choice(g, id)),
+        5 => ChoiceValue::Rectangle(choice(g, rectangle)),
+        6 => ChoiceValue::Fraction(choice(g, fraction)),
+        _ => ChoiceValue::Fd(choice(g, fd)),
+    }
+}
+
+fn value(g: &mut Gen, depth: usize) -> Value {
+    // Structs and objects are only generated while the maximum depth has not been reached.
+    let kinds = if depth == 0 { 16 } else { 18 };
+
+    match u8::arbitrary(g) % kinds {
+        0 => Value::None,
+        1 => Value::Bool(bool::arbitrary(g)),
+        2 => Value::Id(id(g)),
+        3 => Value::Int(i32::arbitrary(g)),
+        4 => Value::Long(i64::arbitrary(g)),
+        5 => Value::Float(float(g)),
+        6 => Value::Double(double(g)),
+        // Strings are serialized as C strings, so they can not contain 0 bytes.
+        7 => Value::String(String::arbitrary(g).replace('\0', "")),
+        8 => Value::Bytes(Vec::arbitrary(g)),
+        9 => Value::Rectangle(rectangle(g)),
+        10 => Value::Fraction(fraction(g)),
+        11 => Value::Fd(fd(g)),
+        12 => Value::ValueArray(array(g)),
+        13 => Value::Choice(choice_value(g)),
+        14 => Value::Pointer(u32::arbitrary(g), usize::arbitrary(g) as *const c_void),
+        15 => Value::Unknown {
+            // Types past the range of the basic types are never supported by the deserializer.
+            type_: 0x10000 + u32::from(u16::arbitrary(g)),
+            body: Vec::arbitrary(g),
+        },
+        16 => {
+            let len = usize::arbitrary(g) % (MAX_CHILDREN + 1);
+            Value::Struct((0..len).map(|_| value(g, depth - 1)).collect())
+        }
+        _ => {
+            let len = usize::arbitrary(g) % (MAX_CHILDREN + 1);
+            Value::Object(Object {
+                type_: u32::arbitrary(g),
+                id: u32::arbitrary(g),
+                properties: (0..len)
+                    .map(|_| Property {
+                        key: u32::arbitrary(g),
+                        flags: PropertyFlags::from_bits_truncate(u32::arbitrary(g)),
+                        value: value(g, depth - 1),
+                    })
+                    .collect(),
+            })
+        }
+    }
+}
+
+fn roundtrip(value: ArbitraryValue) -> TestResult {
+    let value = value.0;
+
+    let (serialized, len) = match PodSerializer::serialize(Cursor::new(Vec::new()), &value) {
+        Ok((serialized, len)) => (serialized.into_inner(), len),
+        Err(err) => return TestResult::error(format!("Failed to serialize: {:?}", err)),
+    };
+    if serialized.len() != value.serialized_size() || len as usize != serialized.len() {
+        return TestResult::error(format!(
+            "Serialized {} bytes and reported {}, but serialized_size() is {}",
+            serialized.len(),
+            len,
+            value.serialized_size()
+        ));
+    }
+
+    match PodDeserializer::deserialize_any_from_keep_unknown(&serialized) {
+        Ok((remaining, deserialized)) => {
+            TestResult::from_bool(remaining.is_empty() && deserialized == value)
+        }
+        Err(err) => TestResult::error(format!("Failed to deserialize: {:?}", err)),
+    }
+}
+
+#[test]
+fn value_roundtrip() {
+    QuickCheck::new()
+        .tests(1000)
+        .quickcheck(roundtrip as fn(ArbitraryValue) -> TestResult);
+}
@@ -21,9 +21,9 @@ fn main() {
     let mainloop_clone = mainloop.clone();
     let reg_listener = registry
         .add_listener_local()
-        .global(move |global| {
+        .global_filtered(ObjectType::Factory, move |global| {
             if let Some(ref props) = global.props {
-                // Check that the global is a factory that creates the right type.
+                // Check that the factory creates the right type.
                 if props.get("factory.type.name") == Some(ObjectType::Link.to_str()) {
                     let factory_name = props.get("factory.name").expect("Factory has no name");
                     factory_clone
@@ -44,8 +44,10 @@ fn main() {
     std::mem::drop(reg_listener);
 
     // Now that we have the name of a link factory, we can create an object with it!
-    let link = core
-        .create_object::<pw::link::Link, _>(
+    // This also does another roundtrip so that the link gets created on the server side.
+    let (link, link_id) = core
+        .create_object_sync::<pw::link::Link, _>(
+            &mainloop,
             factory.get().expect("No link factory found"),
             &pw::properties! {
                 "link.output.port" => "1",
@@ -57,9 +59,7 @@ fn main() {
             },
         )
         .expect("Failed to create object");
-
-    // Do another roundtrip so that the link gets created on the server side.
-    do_roundtrip(&mainloop, &core);
+    println!("Created link with global id {}", link_id);
 
     // We have our object, now manually destroy it on the remote again.
     core.destroy_object(link).expect("destroy object failed");
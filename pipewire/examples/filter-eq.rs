@@ -0,0 +1,123 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! This program shows how to write a filter with several ports, here a simple equalizer
+//! boosting or cutting the bass of stereo audio, with two input ports and two output ports.
+
+use pipewire as pw;
+use pw::{
+    filter::{Filter, FilterFlags, Port, PortFlags},
+    properties, spa,
+};
+
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "filter-eq", about = "Stereo equalizer filter example")]
+struct Opt {
+    #[structopt(
+        short,
+        long,
+        default_value = "2.0",
+        help = "The gain applied to the bass"
+    )]
+    gain: f32,
+}
+
+/// The coefficient of the one-pole low-pass filter extracting the bass, roughly 150 Hz at 48 kHz.
+const LOW_PASS: f32 = 0.02;
+
+struct Ports<'f> {
+    inputs: [Port<'f, ()>; 2],
+    outputs: [Port<'f, ()>; 2],
+    gain: f32,
+    /// The output of the low-pass filter of each channel.
+    lows: [f32; 2],
+}
+
+pub fn main() -> Result<(), pw::Error> {
+    let _guard = pw::init_guarded();
+
+    let opt = Opt::from_args();
+
+    let mainloop = pw::MainLoop::new()?;
+    let context = pw::Context::new(&mainloop)?;
+    let core = context.connect(None)?;
+
+    let filter = Filter::new(
+        &core,
+        "filter-eq",
+        properties! {
+            *pw::keys::MEDIA_TYPE => "Audio",
+            *pw::keys::MEDIA_CATEGORY => "Filter",
+            *pw::keys::MEDIA_ROLE => "DSP",
+        },
+    )?;
+
+    let dsp_port = |name: &str| {
+        properties! {
+            *pw::keys::FORMAT_DSP => "32 bit float mono audio",
+            *pw::keys::PORT_NAME => name,
+        }
+    };
+    let add_port = |direction, name| {
+        filter.add_port(
+            direction,
+            PortFlags::MAP_BUFFERS,
+            dsp_port(name),
+            &mut [],
+            (),
+        )
+    };
+    let ports = Ports {
+        inputs: [
+            add_port(spa::Direction::Input, "input_FL")?,
+            add_port(spa::Direction::Input, "input_FR")?,
+        ],
+        outputs: [
+            add_port(spa::Direction::Output, "output_FL")?,
+            add_port(spa::Direction::Output, "output_FR")?,
+        ],
+        gain: opt.gain,
+        lows: [0.0; 2],
+    };
+
+    let _listener = filter
+        .add_local_listener_with_user_data(ports)
+        .state_changed(|old, new| {
+            println!("State changed: {:?} -> {:?}", old, new);
+        })
+        .process(|ports, position| {
+            let n_samples = match position {
+                Some(position) => position.duration() as u32,
+                None => return,
+            };
+            let gain = ports.gain;
+
+            for ((input, output), low) in ports
+                .inputs
+                .iter_mut()
+                .zip(ports.outputs.iter_mut())
+                .zip(ports.lows.iter_mut())
+            {
+                // Safety: the buffers hold the samples of the current cycle.
+                let (in_samples, out_samples) =
+                    match unsafe { (input.dsp_buffer(n_samples), output.dsp_buffer(n_samples)) } {
+                        (Some(in_samples), Some(out_samples)) => (in_samples, out_samples),
+                        _ => continue,
+                    };
+
+                for (sample, out) in in_samples.iter().zip(out_samples.iter_mut()) {
+                    *low += LOW_PASS * (sample - *low);
+                    *out = sample + (gain - 1.0) * *low;
+                }
+            }
+        })
+        .register()?;
+
+    filter.connect(FilterFlags::RT_PROCESS, &mut [])?;
+
+    mainloop.run();
+
+    Ok(())
+}
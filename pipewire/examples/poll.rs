@@ -0,0 +1,60 @@
+//! This program shows how to integrate the pipewire loop into another event loop,
+//! here a simple `poll()` based one, instead of running it with `MainLoop::run()`.
+
+use pipewire::*;
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+fn main() {
+    let _guard = pipewire::init_guarded();
+
+    let mainloop = MainLoop::new().expect("Failed to create main loop");
+    let context = Context::new(&mainloop).expect("Failed to create context");
+    let core = context.connect(None).expect("Failed to connect to core");
+    let registry = core.get_registry().expect("Failed to get Registry");
+
+    let done = Rc::new(Cell::new(false));
+    let pending = core.sync_auto().expect("sync failed");
+
+    let _listener_core = core
+        .add_listener_local()
+        .done({
+            let done = done.clone();
+            move |id, seq| {
+                if id == PW_ID_CORE && seq == pending {
+                    done.set(true);
+                }
+            }
+        })
+        .register();
+    let _listener_reg = registry
+        .add_listener_local()
+        .global(|global| {
+            println!(
+                "object: id:{} type:{}/{}",
+                global.id, global.type_, global.version
+            )
+        })
+        .register();
+
+    let mut fds = [libc::pollfd {
+        fd: mainloop.fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    }];
+
+    while !done.get() {
+        // Other file descriptors of the application would be polled here as well.
+        let res = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, 1000) };
+        assert!(res >= 0, "poll failed");
+
+        if fds[0].revents & libc::POLLIN != 0 {
+            // The loop has pending work, process it without blocking.
+            let dispatched = mainloop
+                .dispatch(Some(Duration::ZERO))
+                .expect("Failed to dispatch loop");
+            println!("dispatched {} sources", dispatched);
+        } else {
+            println!("timeout, doing other work");
+        }
+    }
+}
@@ -214,14 +214,10 @@ struct Opt {
 }
 
 fn main() -> Result<()> {
-    pw::init();
+    let _guard = pw::init_guarded();
 
     let opt = Opt::from_args();
     monitor(opt.remote)?;
 
-    unsafe {
-        pw::deinit();
-    }
-
     Ok(())
 }
@@ -4,11 +4,9 @@ use pipewire::*;
 use std::{cell::Cell, rc::Rc};
 
 fn main() {
-    pipewire::init();
+    let _guard = pipewire::init_guarded();
 
     roundtrip();
-
-    unsafe { pipewire::deinit() };
 }
 
 fn roundtrip() {
@@ -19,7 +19,7 @@ struct Opt {
 }
 
 pub fn main() -> Result<(), pw::Error> {
-    pw::init();
+    let _guard = pw::init_guarded();
 
     let opt = Opt::from_args();
 
@@ -68,7 +68,5 @@ pub fn main() -> Result<(), pw::Error> {
 
     mainloop.run();
 
-    unsafe { pw::deinit() };
-
     Ok(())
 }
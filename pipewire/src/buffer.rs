@@ -1,6 +1,6 @@
 use super::stream::Stream;
 
-use crate::data::Data;
+use crate::data::{Data, Plane};
 use std::convert::TryFrom;
 use std::ptr::NonNull;
 
@@ -44,6 +44,46 @@ impl<D> Buffer<'_, D> {
 
         slice_of_data
     }
+
+    /// Get the number of [`Data`] of the buffer, e.g. the number of planes of a planar video format.
+    ///
+    /// This is the length of the slice returned by [`datas_mut`](`Self::datas_mut`).
+    pub fn n_datas(&self) -> usize {
+        let buffer: *mut spa_sys::spa_buffer = unsafe { self.buf.as_ref().buffer };
+
+        if !buffer.is_null() && unsafe { !(*buffer).datas.is_null() } {
+            usize::try_from(unsafe { (*buffer).n_datas }).unwrap()
+        } else {
+            0
+        }
+    }
+
+    /// Get the [`Data`] at `index` of the buffer.
+    ///
+    /// Returns `None` if `index` is out of range, see [`n_datas`](`Self::n_datas`).
+    pub fn data(&mut self, index: usize) -> Option<&mut Data> {
+        self.datas_mut().get_mut(index)
+    }
+
+    /// Get the plane at `index` of the buffer.
+    ///
+    /// Planar formats, such as NV12 or I420 video, use one [`Data`] per plane.
+    /// The returned [`Plane`] gives access to the memory of the plane as well as its [`Chunk`](`crate::data::Chunk`),
+    /// which holds the size, offset and stride of the plane.
+    ///
+    /// Returns `None` if there is no data at `index`, or if its memory is not mapped.
+    pub fn plane(&mut self, index: usize) -> Option<Plane<'_>> {
+        self.data(index).and_then(Data::plane)
+    }
+
+    /// Get the number of frames requested by the graph for this buffer.
+    ///
+    /// This is only set on buffers of output streams, and is `0` if no specific amount was requested.
+    /// Producing exactly this amount of frames avoids underruns and overruns.
+    #[cfg(feature = "v0_3_49")]
+    pub fn requested(&self) -> u64 {
+        unsafe { self.buf.as_ref().requested }
+    }
 }
 
 impl<D> Drop for Buffer<'_, D> {
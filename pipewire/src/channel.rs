@@ -157,6 +157,22 @@ impl<T> Sender<T> {
     ///
     /// On any errors, this returns the message back to the caller.
     pub fn send(&self, t: T) -> Result<(), T> {
+        self.push(t, false)
+    }
+
+    /// Send a message to the associated receiver, replacing any message that has not been received yet.
+    ///
+    /// This is useful when only the latest value matters, e.g. for parameter updates from a UI,
+    /// so that a slow receiver does not have to process a backlog of stale values.
+    /// Messages sent with [`send`](`Self::send`) that are still waiting are replaced as well.
+    ///
+    /// On any errors, this returns the message back to the caller.
+    pub fn send_latest(&self, t: T) -> Result<(), T> {
+        self.push(t, true)
+    }
+
+    /// Push a message into the queue, clearing the queue first if `replace` is `true`.
+    fn push(&self, t: T, replace: bool) -> Result<(), T> {
         // Lock the channel.
         let mut channel = match self.channel.lock() {
             Ok(chan) => chan,
@@ -179,6 +195,10 @@ impl<T> Sender<T> {
             }
         }
 
+        if replace {
+            channel.queue.clear();
+        }
+
         // Push the new message into the queue.
         channel.queue.push_back(t);
 
@@ -244,3 +264,33 @@ where
         Receiver { channel },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainLoop;
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    #[test]
+    fn send_latest() {
+        let mainloop = MainLoop::new().unwrap();
+        let (sender, receiver) = channel();
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let _receiver = receiver.attach(&mainloop, {
+            let received = received.clone();
+            move |value: u32| received.borrow_mut().push(value)
+        });
+
+        sender.send(1).unwrap();
+        sender.send_latest(2).unwrap();
+        sender.send_latest(3).unwrap();
+        mainloop.dispatch(Some(Duration::ZERO)).unwrap();
+        assert_eq!(*received.borrow(), vec![3]);
+
+        sender.send(4).unwrap();
+        sender.send(5).unwrap();
+        mainloop.dispatch(Some(Duration::ZERO)).unwrap();
+        assert_eq!(*received.borrow(), vec![3, 4, 5]);
+    }
+}
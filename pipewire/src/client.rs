@@ -0,0 +1,289 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use bitflags::bitflags;
+use libc::c_void;
+use std::pin::Pin;
+use std::{convert::TryInto, ffi::CString, fmt, mem, ptr};
+
+use spa::{
+    dict::{ForeignDict, ReadableDict},
+    result::SpaResult,
+    spa_interface_call_method,
+};
+
+use crate::{
+    proxy::{Listener, Proxy, ProxyT},
+    registry::Permission,
+    types::ObjectType,
+    Error,
+};
+
+/// A client connected to the pipewire daemon, such as an application or the session manager.
+///
+/// Client proxies are obtained by binding the `Client` globals announced by the registry.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Client {
+    proxy: Proxy,
+}
+
+impl crate::proxy::sealed::Sealed for Client {}
+
+impl ProxyT for Client {
+    fn type_() -> ObjectType {
+        ObjectType::Client
+    }
+
+    fn upcast(self) -> Proxy {
+        self.proxy
+    }
+
+    fn upcast_ref(&self) -> &Proxy {
+        &self.proxy
+    }
+
+    unsafe fn from_proxy_unchecked(proxy: Proxy) -> Self
+    where
+        Self: Sized,
+    {
+        Self { proxy }
+    }
+
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized,
+    {
+        // Safety: `Self` is a `#[repr(transparent)]` wrapper around a `Proxy`.
+        &*(proxy as *const Proxy as *const Self)
+    }
+}
+
+impl Client {
+    // TODO: add non-local version when we'll bind pw_thread_loop_start()
+    #[must_use]
+    pub fn add_listener_local(&self) -> ClientListenerLocalBuilder {
+        ClientListenerLocalBuilder {
+            client: self,
+            cbs: ListenerLocalCallbacks::default(),
+        }
+    }
+
+    /// Update the properties of the client.
+    ///
+    /// The properties are merged into the current properties of the client,
+    /// which are then announced to the listeners of the client with the `info` event.
+    ///
+    /// This requires the `W` [`Permission`] on the client.
+    pub fn update_properties<D: ReadableDict>(&self, properties: &D) -> Result<(), Error> {
+        let res = unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr() as *mut pw_sys::pw_client,
+                pw_sys::pw_client_methods,
+                update_properties,
+                properties.get_dict_ptr()
+            )
+        };
+
+        SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+
+    /// Send an error to the client, about its resource of id `id`.
+    ///
+    /// `res` is the negative `errno` of the error, such as `-libc::EACCES`.
+    /// The client receives the error in the `error` event of its core.
+    ///
+    /// This requires the `X` [`Permission`] on the client.
+    ///
+    /// # Panics
+    /// If `message` contains a 0 byte.
+    pub fn error(&self, id: u32, res: i32, message: &str) -> Result<(), Error> {
+        let message = CString::new(message).expect("Invalid byte in error message");
+
+        let res = unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr() as *mut pw_sys::pw_client,
+                pw_sys::pw_client_methods,
+                error,
+                id,
+                res,
+                message.as_ptr()
+            )
+        };
+
+        SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+
+    /// Update the permissions the client has on the global objects.
+    ///
+    /// Each entry is the id of a global and the permissions the client is granted on it.
+    /// Use [`PW_ID_ANY`](`pw_sys::PW_ID_ANY`) as id to set the default permissions, used for the globals
+    /// the client has no specific permissions on.
+    ///
+    /// This requires the `M` [`Permission`] on the client and is usually done by a security manager.
+    pub fn update_permissions(&self, permissions: &[(u32, Permission)]) -> Result<(), Error> {
+        let res = unsafe { update_permissions(self.proxy.as_ptr().cast(), permissions) };
+
+        SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct ListenerLocalCallbacks {
+    info: Option<Box<dyn Fn(&ClientInfo)>>,
+}
+
+pub struct ClientListenerLocalBuilder<'a> {
+    client: &'a Client,
+    cbs: ListenerLocalCallbacks,
+}
+
+/// Information about a client, such as the properties of the process it belongs to.
+pub struct ClientInfo {
+    ptr: ptr::NonNull<pw_sys::pw_client_info>,
+    props: Option<ForeignDict>,
+}
+
+impl ClientInfo {
+    fn new(ptr: ptr::NonNull<pw_sys::pw_client_info>) -> Self {
+        let props_ptr = unsafe { ptr.as_ref().props };
+        let props = ptr::NonNull::new(props_ptr).map(|ptr| unsafe { ForeignDict::from_ptr(ptr) });
+
+        Self { ptr, props }
+    }
+
+    pub fn id(&self) -> u32 {
+        unsafe { self.ptr.as_ref().id }
+    }
+
+    pub fn change_mask(&self) -> ClientChangeMask {
+        let mask = unsafe { self.ptr.as_ref().change_mask };
+        ClientChangeMask::from_bits(mask).expect("invalid change_mask")
+    }
+
+    pub fn props(&self) -> Option<&ForeignDict> {
+        self.props.as_ref()
+    }
+}
+
+bitflags! {
+    pub struct ClientChangeMask: u64 {
+        const PROPS = pw_sys::PW_CLIENT_CHANGE_MASK_PROPS as u64;
+    }
+}
+
+impl fmt::Debug for ClientInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientInfo")
+            .field("id", &self.id())
+            .field("change-mask", &self.change_mask())
+            .field("props", &self.props())
+            .finish()
+    }
+}
+
+pub struct ClientListener {
+    // Need to stay allocated while the listener is registered
+    #[allow(dead_code)]
+    events: Pin<Box<pw_sys::pw_client_events>>,
+    listener: Pin<Box<spa_sys::spa_hook>>,
+    #[allow(dead_code)]
+    data: Box<ListenerLocalCallbacks>,
+}
+
+impl Listener for ClientListener {}
+
+impl Drop for ClientListener {
+    fn drop(&mut self) {
+        spa::hook::remove(*self.listener);
+    }
+}
+
+impl<'a> ClientListenerLocalBuilder<'a> {
+    #[must_use]
+    pub fn info<F>(mut self, info: F) -> Self
+    where
+        F: Fn(&ClientInfo) + 'static,
+    {
+        self.cbs.info = Some(Box::new(info));
+        self
+    }
+
+    #[must_use]
+    pub fn register(self) -> ClientListener {
+        unsafe extern "C" fn client_events_info(
+            data: *mut c_void,
+            info: *const pw_sys::pw_client_info,
+        ) {
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let info = ptr::NonNull::new(info as *mut _).expect("info is NULL");
+                let info = ClientInfo::new(info);
+                callbacks.info.as_ref().unwrap()(&info);
+            })
+        }
+
+        let e = unsafe {
+            let mut e: Pin<Box<pw_sys::pw_client_events>> = Box::pin(mem::zeroed());
+            e.version = pw_sys::PW_VERSION_CLIENT_EVENTS;
+
+            if self.cbs.info.is_some() {
+                e.info = Some(client_events_info);
+            }
+
+            e
+        };
+
+        let (listener, data) = unsafe {
+            let client = &self.client.proxy.as_ptr();
+
+            let data = Box::into_raw(Box::new(self.cbs));
+            let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+            let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
+            let funcs: *const pw_sys::pw_client_events = e.as_ref().get_ref();
+
+            pw_sys::pw_proxy_add_object_listener(
+                client.cast(),
+                listener_ptr.cast(),
+                funcs.cast(),
+                data as *mut _,
+            );
+
+            (listener, Box::from_raw(data))
+        };
+
+        ClientListener {
+            events: e,
+            listener,
+            data,
+        }
+    }
+}
+
+/// Call the `update_permissions` method on a raw `pw_client` proxy.
+///
+/// # Safety
+/// `client` must be a valid `pw_client` proxy.
+pub(crate) unsafe fn update_permissions(
+    client: *mut pw_sys::pw_client,
+    permissions: &[(u32, Permission)],
+) -> i32 {
+    let permissions: Vec<pw_sys::pw_permission> = permissions
+        .iter()
+        .map(|(id, permissions)| pw_sys::pw_permission {
+            id: *id,
+            permissions: permissions.bits(),
+        })
+        .collect();
+
+    spa_interface_call_method!(
+        client,
+        pw_sys::pw_client_methods,
+        update_permissions,
+        permissions.len().try_into().expect("Too many permissions"),
+        permissions.as_ptr()
+    )
+}
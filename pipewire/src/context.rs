@@ -1,12 +1,17 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::{os::unix::prelude::RawFd, ptr};
+use std::{
+    os::unix::prelude::{IntoRawFd, RawFd},
+    ptr,
+};
+
+use spa::prelude::*;
 
 use crate::core_::Core;
 use crate::error::Error;
 use crate::loop_::Loop;
-use crate::properties::Properties;
+use crate::properties::{Properties, PropertiesRef};
 
 #[derive(Debug)]
 pub struct Context<T: Loop + Clone> {
@@ -32,6 +37,32 @@ impl<T: Loop + Clone> Context<T> {
         Self::new_internal(loop_, None)
     }
 
+    /// Create a new context using the provided properties.
+    ///
+    /// The properties configure the context, and can for example be used to select the implementation
+    /// of the DBus support library using [`LIBRARY_NAME_DBUS`](`crate::keys::LIBRARY_NAME_DBUS`).
+    ///
+    /// Note that the system and loop support libraries are loaded when creating the loop,
+    /// so [`LIBRARY_NAME_SYSTEM`](`crate::keys::LIBRARY_NAME_SYSTEM`) and
+    /// [`LIBRARY_NAME_LOOP`](`crate::keys::LIBRARY_NAME_LOOP`) have to be passed to
+    /// [`MainLoop::with_properties`](`crate::MainLoop::with_properties`) instead.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use pipewire::{keys, properties, Context, MainLoop};
+    ///
+    /// let mainloop = MainLoop::with_properties(&properties! {
+    ///     *keys::LIBRARY_NAME_SYSTEM => "support/libspa-support",
+    ///     *keys::LIBRARY_NAME_LOOP => "support/libspa-support",
+    /// })?;
+    /// let context = Context::with_properties(
+    ///     &mainloop,
+    ///     properties! {
+    ///         *keys::LIBRARY_NAME_DBUS => "support/libspa-dbus",
+    ///     },
+    /// )?;
+    /// # Ok::<(), pipewire::Error>(())
+    /// ```
     pub fn with_properties(loop_: &T, properties: Properties) -> Result<Self, Error> {
         Self::new_internal(loop_, Some(properties))
     }
@@ -40,27 +71,146 @@ impl<T: Loop + Clone> Context<T> {
         self.ptr.as_ptr()
     }
 
+    /// Get the raw `pw_context` pointer, to call functions of libpipewire that are not bound yet.
+    ///
+    /// # Safety
+    /// The pointer is only valid for the lifetime of the `Context`, and ownership of the `pw_context`
+    /// is not transferred to the caller, so it must not be destroyed.
+    /// The state of the context must not be changed in ways the bindings do not expect,
+    /// such as removing listeners registered by the bindings.
+    pub unsafe fn as_raw(&self) -> *mut pw_sys::pw_context {
+        self.as_ptr()
+    }
+
+    /// Get the properties of the context.
+    ///
+    /// These include the properties the context was created with, as well as the defaults set by pipewire.
+    pub fn properties(&self) -> PropertiesRef<'_> {
+        unsafe {
+            let props = pw_sys::pw_context_get_properties(self.as_ptr());
+            let props = ptr::NonNull::new(props as *mut _).expect("context has no properties");
+            PropertiesRef::from_ptr(props)
+        }
+    }
+
+    /// Connect to the pipewire daemon.
+    ///
+    /// The daemon to connect to is selected by the [`REMOTE_NAME`](`crate::keys::REMOTE_NAME`) property
+    /// of `properties` if set. Otherwise libpipewire uses the `PIPEWIRE_REMOTE` environment variable,
+    /// and falls back to the default `pipewire-0` remote.
+    /// The socket of the remote is looked up in the directory set by the `PIPEWIRE_RUNTIME_DIR`
+    /// or `XDG_RUNTIME_DIR` environment variables.
+    ///
+    /// Returns [`Error::ConnectionFailed`] with the reported `errno` if the connection failed,
+    /// use [`Error::is_daemon_not_running`] to check whether the daemon is not running.
     pub fn connect(&self, properties: Option<Properties>) -> Result<Core, Error> {
         let properties = properties.map_or(ptr::null_mut(), |p| p.into_raw());
 
         unsafe {
             let core = pw_sys::pw_context_connect(self.as_ptr(), properties, 0);
-            let ptr = ptr::NonNull::new(core).ok_or(Error::CreationFailed)?;
+            let ptr =
+                ptr::NonNull::new(core).ok_or_else(|| Error::ConnectionFailed(errno::errno()))?;
 
             Ok(Core::from_ptr(ptr))
         }
     }
 
+    /// Connect to the pipewire daemon, stating the intention of the connection.
+    ///
+    /// This sets the [`REMOTE_INTENTION`](`crate::keys::REMOTE_INTENTION`) property
+    /// in `properties` before connecting, see [`connect`](`Self::connect`) for details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use pipewire::{Context, ConnectIntention, MainLoop};
+    ///
+    /// let mainloop = MainLoop::new()?;
+    /// let context = Context::new(&mainloop)?;
+    /// let core = context.connect_with_intention(ConnectIntention::Screencast, None)?;
+    /// # Ok::<(), pipewire::Error>(())
+    /// ```
+    pub fn connect_with_intention(
+        &self,
+        intention: ConnectIntention,
+        properties: Option<Properties>,
+    ) -> Result<Core, Error> {
+        let mut properties = properties.unwrap_or_default();
+        properties.insert(*crate::keys::REMOTE_INTENTION, intention.as_str());
+
+        self.connect(Some(properties))
+    }
+
+    /// Connect to the pipewire daemon using an already connected socket.
+    ///
+    /// This is used by sandboxed applications, which are handed a socket to the daemon by
+    /// e.g. the `OpenPipeWireRemote` method of the screen cast portal.
+    ///
+    /// Pipewire takes ownership of `fd` and closes it once the connection is closed,
+    /// so `fd` must not be closed by the caller afterwards.
+    /// Use [`connect_owned_fd`](`Self::connect_owned_fd`) to hand over a file descriptor owned by a Rust type.
+    ///
+    /// Returns [`Error::ConnectionFailed`] with the reported `errno` if the connection failed.
     pub fn connect_fd(&self, fd: RawFd, properties: Option<Properties>) -> Result<Core, Error> {
         let properties = properties.map_or(ptr::null_mut(), |p| p.into_raw());
 
         unsafe {
             let core = pw_sys::pw_context_connect_fd(self.as_ptr(), fd, properties, 0);
-            let ptr = ptr::NonNull::new(core).ok_or(Error::CreationFailed)?;
+            let ptr =
+                ptr::NonNull::new(core).ok_or_else(|| Error::ConnectionFailed(errno::errno()))?;
 
             Ok(Core::from_ptr(ptr))
         }
     }
+
+    /// Variant of [`connect_fd`](`Self::connect_fd`) taking ownership of the socket.
+    ///
+    /// The file descriptor is released from `fd` and closed by pipewire once the connection is closed,
+    /// so that it is not closed twice.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use pipewire::{Context, MainLoop};
+    /// use std::os::unix::{io::FromRawFd, net::UnixStream};
+    ///
+    /// # fn portal_fd() -> std::os::unix::io::RawFd { unimplemented!() }
+    /// let mainloop = MainLoop::new()?;
+    /// let context = Context::new(&mainloop)?;
+    ///
+    /// // The socket returned by the `OpenPipeWireRemote` method of the screen cast portal.
+    /// let socket = unsafe { UnixStream::from_raw_fd(portal_fd()) };
+    /// let core = context.connect_owned_fd(socket, None)?;
+    /// # Ok::<(), pipewire::Error>(())
+    /// ```
+    pub fn connect_owned_fd<F: IntoRawFd>(
+        &self,
+        fd: F,
+        properties: Option<Properties>,
+    ) -> Result<Core, Error> {
+        self.connect_fd(fd.into_raw_fd(), properties)
+    }
+}
+
+/// The intention of a connection to the pipewire daemon, used with
+/// [`Context::connect_with_intention`].
+///
+/// The intention is stored in the [`REMOTE_INTENTION`](`crate::keys::REMOTE_INTENTION`)
+/// property of the connection, and lets the daemon and session manager apply the matching policy.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConnectIntention {
+    /// A generic connection, the default.
+    Generic,
+    /// A connection used to capture the screen, such as the ones set up by the screen cast portal.
+    Screencast,
+}
+
+impl ConnectIntention {
+    /// The value of the [`REMOTE_INTENTION`](`crate::keys::REMOTE_INTENTION`) property for this intention.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectIntention::Generic => "generic",
+            ConnectIntention::Screencast => "screencast",
+        }
+    }
 }
 
 impl<T: Loop + Clone> Drop for Context<T> {
@@ -68,3 +218,59 @@ impl<T: Loop + Clone> Drop for Context<T> {
         unsafe { pw_sys::pw_context_destroy(self.as_ptr()) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keys, properties, MainLoop};
+    use spa::prelude::*;
+
+    #[test]
+    fn connect_intention() {
+        assert_eq!(ConnectIntention::Generic.as_str(), "generic");
+        assert_eq!(ConnectIntention::Screencast.as_str(), "screencast");
+    }
+
+    #[test]
+    fn with_properties() {
+        let mainloop = MainLoop::new().unwrap();
+        let context = Context::with_properties(
+            &mainloop,
+            properties! {
+                *keys::LIBRARY_NAME_DBUS => "support/libspa-dbus",
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            context.properties().get(*keys::LIBRARY_NAME_DBUS),
+            Some("support/libspa-dbus")
+        );
+    }
+
+    #[test]
+    fn with_properties_library_name() {
+        let mainloop = MainLoop::new().unwrap();
+        // Failing to load the DBus support library is not fatal,
+        // the context is created without DBus support instead.
+        let context = Context::with_properties(
+            &mainloop,
+            properties! {
+                *keys::LIBRARY_NAME_DBUS => "support/does-not-exist",
+            },
+        )
+        .unwrap();
+
+        let has_dbus = unsafe {
+            let mut n_support = 0;
+            let support = pw_sys::pw_context_get_support(context.as_raw(), &mut n_support);
+            std::slice::from_raw_parts(support, n_support as usize)
+                .iter()
+                .any(|support| {
+                    std::ffi::CStr::from_ptr(support.type_).to_bytes()
+                        == b"Spa:Pointer:Interface:DBus"
+                })
+        };
+        assert!(!has_dbus);
+    }
+}
@@ -4,6 +4,7 @@
 use bitflags::bitflags;
 use libc::{c_char, c_void};
 use std::{
+    cell::{Cell, RefCell},
     ffi::{CStr, CString},
     rc::Rc,
 };
@@ -12,8 +13,8 @@ use std::{ops::Deref, pin::Pin};
 
 use crate::{
     proxy::{Proxy, ProxyT},
-    registry::Registry,
-    Error,
+    registry::{Permission, Registry},
+    Error, MainLoop,
 };
 use spa::{dict::ForeignDict, result::SpaResult, spa_interface_call_method, AsyncSeq};
 
@@ -43,11 +44,16 @@ impl Deref for Core {
 #[derive(Debug)]
 pub struct CoreInner {
     ptr: ptr::NonNull<pw_sys::pw_core>,
+    /// The sequence number used by the next call to [`sync_auto`](`Self::sync_auto`).
+    sync_seq: Cell<i32>,
 }
 
 impl CoreInner {
     fn from_ptr(ptr: ptr::NonNull<pw_sys::pw_core>) -> Self {
-        Self { ptr }
+        Self {
+            ptr,
+            sync_seq: Cell::new(0),
+        }
     }
 
     /// Get the underlying pointer for this `Core`.
@@ -60,6 +66,17 @@ impl CoreInner {
         self.ptr.as_ptr()
     }
 
+    /// Get the raw `pw_core` pointer, to call functions of libpipewire that are not bound yet.
+    ///
+    /// # Safety
+    /// The pointer is only valid for the lifetime of the `Core` and its clones, and ownership of the `pw_core`
+    /// is not transferred to the caller, so it must not be disconnected.
+    /// The state of the core must not be changed in ways the bindings do not expect,
+    /// such as removing listeners registered by the bindings.
+    pub unsafe fn as_raw(&self) -> *mut pw_sys::pw_core {
+        self.as_ptr()
+    }
+
     // TODO: add non-local version when we'll bind pw_thread_loop_start()
     #[must_use]
     pub fn add_listener_local(&self) -> ListenerLocalBuilder {
@@ -99,6 +116,81 @@ impl CoreInner {
         Ok(res)
     }
 
+    /// Variant of [`sync`](`Self::sync`) using a sequence number managed by the core.
+    ///
+    /// Each call uses a new sequence number, incremented from the previous one, so callers do not have
+    /// to track sequence numbers themselves.
+    ///
+    /// The server does not echo the sequence number passed to it, so the `done` event of a sync must be
+    /// matched against the returned [`AsyncSeq`], not against the sequence number.
+    pub fn sync_auto(&self) -> Result<AsyncSeq, Error> {
+        let seq = self.sync_seq.get();
+        self.sync_seq.set(seq.wrapping_add(1) & i32::MAX);
+
+        self.sync(seq)
+    }
+
+    /// Do a roundtrip to the server by running `main_loop` until the server has processed all pending requests.
+    ///
+    /// # Errors
+    /// Returns [`Error::ServerError`] as soon as the server reports an error about the core itself,
+    /// such as the connection being lost (`EPIPE`), as the roundtrip could then never complete.
+    ///
+    /// Errors about other objects, such as an object that could not be created, do not interrupt the roundtrip.
+    /// Use [`roundtrip_for`](`Self::roundtrip_for`) to catch them.
+    ///
+    /// # Panics
+    /// If `main_loop` is already running, e.g. if this is called from a callback of the loop,
+    /// see [`MainLoop::run`](`crate::MainLoopInner::run`).
+    pub fn roundtrip(&self, main_loop: &MainLoop) -> Result<(), Error> {
+        self.roundtrip_for(main_loop, &[])
+    }
+
+    /// Variant of [`roundtrip`](`Self::roundtrip`) also returning [`Error::ServerError`] as soon as
+    /// the server reports an error about one of the objects whose id is in `ids`,
+    /// such as the [`id`](`Proxy::id`) of a proxy that was just created.
+    ///
+    /// # Panics
+    /// If `main_loop` is already running, e.g. if this is called from a callback of the loop,
+    /// see [`MainLoop::run`](`crate::MainLoopInner::run`).
+    pub fn roundtrip_for(&self, main_loop: &MainLoop, ids: &[u32]) -> Result<(), Error> {
+        let result = Rc::new(RefCell::new(None));
+        let pending = self.sync_auto()?;
+        let ids = ids.to_vec();
+        let _listener = self
+            .add_listener_local()
+            .done({
+                let result = result.clone();
+                let main_loop = main_loop.clone();
+                move |id, seq| {
+                    if id == PW_ID_CORE && seq == pending {
+                        result.borrow_mut().get_or_insert(Ok(()));
+                        main_loop.quit();
+                    }
+                }
+            })
+            .error({
+                let result = result.clone();
+                let main_loop = main_loop.clone();
+                move |id, seq, res, message| {
+                    if id == PW_ID_CORE || ids.contains(&id) {
+                        result
+                            .borrow_mut()
+                            .get_or_insert(Err(Error::from_core_error(id, seq, res, message)));
+                        main_loop.quit();
+                    }
+                }
+            })
+            .register();
+
+        loop {
+            if let Some(result) = result.borrow_mut().take() {
+                return result;
+            }
+            main_loop.run();
+        }
+    }
+
     /// Create a new object on the PipeWire server from a factory.
     ///
     /// You will need specify what type you are expecting to be constructed by either using type inference or the
@@ -175,6 +267,64 @@ impl CoreInner {
         Proxy::new(ptr).downcast().map_err(|(_, e)| e)
     }
 
+    /// Variant of [`create_object`](`Self::create_object`) waiting for the object to be created on the server.
+    ///
+    /// After creating the object, this does a roundtrip to the server by running `main_loop`
+    /// until the server has processed the request.
+    ///
+    /// # Returns
+    /// One of:
+    /// - `Ok((P, u32))` on success, where `P` is the newly created object and the `u32` is its global id
+    /// - `Err(Error::CreationFailed)` if the object could not be created or was not bound to a global by the server
+    /// - `Err(Error::ServerError { .. })` if the server reported an error while creating the object
+    ///   or an error on the connection
+    /// - `Err(Error::WrongProxyType)` if the created type does not match the type `P` that the user is trying to create
+    ///
+    /// # Panics
+    /// If `factory_name` contains a null byte, or if `main_loop` is already running,
+    /// see [`roundtrip_for`](`Self::roundtrip_for`).
+    pub fn create_object_sync<P: ProxyT, D: crate::spa::dict::ReadableDict>(
+        &self,
+        main_loop: &MainLoop,
+        factory_name: &str,
+        properties: &D,
+    ) -> Result<(P, u32), Error> {
+        let object: P = self.create_object(factory_name, properties)?;
+        let proxy_id = object.upcast_ref().id();
+
+        let bound_id = Rc::new(Cell::new(None));
+        let _proxy_listener = object
+            .upcast_ref()
+            .add_listener_local()
+            .bound({
+                let bound_id = bound_id.clone();
+                move |id| bound_id.set(Some(id))
+            })
+            .register();
+
+        self.roundtrip_for(main_loop, &[proxy_id])?;
+
+        match bound_id.get() {
+            Some(id) => Ok((object, id)),
+            None => Err(Error::CreationFailed),
+        }
+    }
+
+    /// Update the permissions of the client of this connection on the global objects.
+    ///
+    /// See [`Client::update_permissions`](`crate::client::Client::update_permissions`),
+    /// which can be used to update the permissions of other clients.
+    pub fn update_permissions(&self, permissions: &[(u32, Permission)]) -> Result<(), Error> {
+        let res = unsafe {
+            // The client proxy is owned by the core, so it must not be destroyed.
+            let client = pw_sys::pw_core_get_client(self.as_ptr());
+            crate::client::update_permissions(client, permissions)
+        };
+
+        SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+
     /// Destroy the object on the remote server represented by the provided proxy.
     ///
     /// The proxy will be destroyed alongside the server side ressource, as it is no longer needed.
@@ -191,6 +341,46 @@ impl CoreInner {
         let res = SpaResult::from_c(res).into_async_result()?;
         Ok(res)
     }
+
+    /// Register a callback called when the connection to the server is lost, e.g. because the server was restarted.
+    ///
+    /// The server reports this through the `error` event of the core, with an `EPIPE` error about the core itself.
+    ///
+    /// Once the connection is lost, the core and all the proxies created through it can not be used anymore.
+    /// To survive a restart of the server, the callback can drop them and connect again
+    /// using [`Context::connect`](`crate::Context::connect`).
+    ///
+    /// The callback is called as long as the returned [`Listener`] is alive.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use pipewire as pw;
+    ///
+    /// let mainloop = pw::MainLoop::new()?;
+    /// let context = pw::Context::new(&mainloop)?;
+    /// let core = context.connect(None)?;
+    ///
+    /// let _listener = core.on_disconnect({
+    ///     let mainloop = mainloop.clone();
+    ///     move || mainloop.quit()
+    /// });
+    ///
+    /// mainloop.run();
+    /// # Ok::<(), pw::Error>(())
+    /// ```
+    #[must_use]
+    pub fn on_disconnect<F>(&self, callback: F) -> Listener
+    where
+        F: Fn() + 'static,
+    {
+        self.add_listener_local()
+            .error(move |id, _seq, res, _message| {
+                if id == PW_ID_CORE && res == -libc::EPIPE {
+                    callback();
+                }
+            })
+            .register()
+    }
 }
 
 #[derive(Default)]
@@ -198,7 +388,7 @@ struct ListenerLocalCallbacks {
     info: Option<Box<dyn Fn(&Info)>>,
     done: Option<Box<dyn Fn(u32, AsyncSeq)>>,
     #[allow(clippy::type_complexity)]
-    error: Option<Box<dyn Fn(u32, i32, i32, &str)>>, // TODO: return a proper Error enum?
+    error: Option<Box<dyn Fn(u32, i32, i32, &str)>>, // See `Error::from_core_error`
                                                      // TODO: ping, remove_id, bound_id, add_mem, remove_mem
 }
 
@@ -262,14 +452,18 @@ impl<'a> ListenerLocalBuilder<'a> {
             data: *mut c_void,
             info: *const pw_sys::pw_core_info,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let info = Info::new(ptr::NonNull::new(info as *mut _).expect("info is NULL"));
-            callbacks.info.as_ref().unwrap()(&info);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let info = Info::new(ptr::NonNull::new(info as *mut _).expect("info is NULL"));
+                callbacks.info.as_ref().unwrap()(&info);
+            })
         }
 
         unsafe extern "C" fn core_events_done(data: *mut c_void, id: u32, seq: i32) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.done.as_ref().unwrap()(id, AsyncSeq::from_raw(seq));
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.done.as_ref().unwrap()(id, AsyncSeq::from_raw(seq));
+            })
         }
 
         unsafe extern "C" fn core_events_error(
@@ -279,9 +473,11 @@ impl<'a> ListenerLocalBuilder<'a> {
             res: i32,
             message: *const c_char,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let message = CStr::from_ptr(message).to_str().unwrap();
-            callbacks.error.as_ref().unwrap()(id, seq, res, message);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let message = CStr::from_ptr(message).to_str().unwrap();
+                callbacks.error.as_ref().unwrap()(id, seq, res, message);
+            })
         }
 
         let e = unsafe {
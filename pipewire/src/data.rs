@@ -5,13 +5,39 @@ pub struct Data(spa_sys::spa_data);
 #[repr(transparent)]
 pub struct Chunk(spa_sys::spa_chunk);
 
+/// A plane of a buffer, bundling the memory of a [`Data`] with its [`Chunk`].
+///
+/// The fields are public so that both can be borrowed at the same time,
+/// e.g. to read the stride from the chunk while writing to the data.
+pub struct Plane<'a> {
+    /// The mapped memory of the plane.
+    pub data: &'a mut [u8],
+    /// The chunk describing the valid region of `data`.
+    pub chunk: &'a mut Chunk,
+}
+
 impl Data {
+    /// Get the memory of the data.
+    ///
+    /// Returns an empty slice if the memory is not mapped, see [`try_get_mut`](`Self::try_get_mut`).
     pub fn get_mut(&mut self) -> &mut [u8] {
+        self.try_get_mut().unwrap_or(&mut [])
+    }
+
+    /// Get the memory of the data.
+    ///
+    /// Returns `None` if the memory is not mapped, e.g. for a DmaBuf that is passed as a file descriptor
+    /// or when the stream was not connected with the `MAP_BUFFERS` flag.
+    pub fn try_get_mut(&mut self) -> Option<&mut [u8]> {
+        if self.0.data.is_null() {
+            return None;
+        }
+
         unsafe {
-            std::slice::from_raw_parts_mut(
+            Some(std::slice::from_raw_parts_mut(
                 self.0.data as *mut u8,
                 usize::try_from(self.0.maxsize).unwrap(),
-            )
+            ))
         }
     }
 
@@ -22,6 +48,18 @@ impl Data {
             &mut *(chunk as *mut Chunk)
         }
     }
+
+    /// Get both the memory and the chunk of the data as a [`Plane`].
+    ///
+    /// Returns `None` if the memory is not mapped or if the data has no chunk.
+    pub fn plane(&mut self) -> Option<Plane<'_>> {
+        if self.0.chunk.is_null() {
+            return None;
+        }
+
+        let chunk = unsafe { &mut *(self.0.chunk as *mut Chunk) };
+        self.try_get_mut().map(|data| Plane { data, chunk })
+    }
 }
 
 impl Chunk {
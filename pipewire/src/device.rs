@@ -0,0 +1,357 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use bitflags::bitflags;
+use libc::c_void;
+use std::pin::Pin;
+use std::{fmt, mem};
+use std::{io::Cursor, ptr};
+
+use crate::{
+    proxy::{Listener, Proxy, ProxyListener, ProxyT},
+    types::ObjectType,
+    Error,
+};
+use spa::{
+    dict::ForeignDict,
+    pod::{deserialize::PodDeserializer, serialize::PodSerializer, Object, Value},
+    result::SpaResult,
+    spa_interface_call_method,
+};
+
+/// A device, such as a sound card or a bluetooth device, managed by the session manager.
+///
+/// The profile of a card and the routes of its nodes are selected by setting the
+/// `Profile` and `Route` params of the device.
+///
+/// # Examples
+/// Select the profile of index 1 of a device:
+/// ```no_run
+/// use pipewire::{device::Device, spa::pod::{Object, Property, PropertyFlags, Value}};
+///
+/// # fn device() -> Device { unimplemented!() }
+/// let device = device();
+/// let profile = Object {
+///     type_: spa_sys::SPA_TYPE_OBJECT_ParamProfile,
+///     id: spa_sys::spa_param_type_SPA_PARAM_Profile,
+///     properties: vec![Property {
+///         key: spa_sys::spa_param_profile_SPA_PARAM_PROFILE_index,
+///         flags: PropertyFlags::empty(),
+///         value: Value::Int(1),
+///     }],
+/// };
+/// device.set_param(spa_sys::spa_param_type_SPA_PARAM_Profile, 0, &profile)?;
+/// # Ok::<(), pipewire::Error>(())
+/// ```
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Device {
+    proxy: Proxy,
+}
+
+impl crate::proxy::sealed::Sealed for Device {}
+
+impl ProxyT for Device {
+    fn type_() -> ObjectType {
+        ObjectType::Device
+    }
+
+    fn upcast(self) -> Proxy {
+        self.proxy
+    }
+
+    fn upcast_ref(&self) -> &Proxy {
+        &self.proxy
+    }
+
+    unsafe fn from_proxy_unchecked(proxy: Proxy) -> Self
+    where
+        Self: Sized,
+    {
+        Self { proxy }
+    }
+
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized,
+    {
+        // Safety: `Self` is a `#[repr(transparent)]` wrapper around a `Proxy`.
+        &*(proxy as *const Proxy as *const Self)
+    }
+}
+
+impl Device {
+    // TODO: add non-local version when we'll bind pw_thread_loop_start()
+    #[must_use]
+    pub fn add_listener_local(&self) -> DeviceListenerLocalBuilder {
+        DeviceListenerLocalBuilder {
+            device: self,
+            cbs: ListenerLocalCallbacks::default(),
+            error: None,
+        }
+    }
+
+    /// Enumerate the params of type `id` of the device, such as the available profiles
+    /// with `SPA_PARAM_EnumProfile`.
+    ///
+    /// At most `num` params are enumerated, starting from the param of index `start`.
+    /// Each param is passed to the `param` callback of the listeners of the device,
+    /// along with `seq`, so that the replies to the different calls can be told apart.
+    pub fn enum_params(&self, seq: i32, id: u32, start: u32, num: u32) -> Result<(), Error> {
+        let res = unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr() as *mut pw_sys::pw_device,
+                pw_sys::pw_device_methods,
+                enum_params,
+                seq,
+                id,
+                start,
+                num,
+                ptr::null()
+            )
+        };
+
+        SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+
+    /// Set the param of type `id` of the device, such as the active profile with `SPA_PARAM_Profile`
+    /// or the route of a node with `SPA_PARAM_Route`.
+    ///
+    /// This requires the client to have the `W` [`Permission`](`crate::registry::Permission`) on the device.
+    ///
+    /// Returns [`Error::SerializationFailed`] if `param` can not be serialized.
+    pub fn set_param(&self, id: u32, flags: u32, param: &Object) -> Result<(), Error> {
+        let (param, _) = PodSerializer::serialize(Cursor::new(Vec::new()), param)?;
+        let param = param.into_inner();
+
+        let res = unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr() as *mut pw_sys::pw_device,
+                pw_sys::pw_device_methods,
+                set_param,
+                id,
+                flags,
+                param.as_ptr() as *const spa_sys::spa_pod
+            )
+        };
+
+        SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct ListenerLocalCallbacks {
+    info: Option<Box<dyn Fn(&DeviceInfo)>>,
+    #[allow(clippy::type_complexity)]
+    param: Option<Box<dyn Fn(i32, u32, u32, u32, Option<&Object>)>>,
+}
+
+pub struct DeviceListenerLocalBuilder<'a> {
+    device: &'a Device,
+    cbs: ListenerLocalCallbacks,
+    #[allow(clippy::type_complexity)]
+    error: Option<Box<dyn Fn(i32, i32, &str)>>,
+}
+
+pub struct DeviceInfo {
+    ptr: ptr::NonNull<pw_sys::pw_device_info>,
+    props: Option<ForeignDict>,
+}
+
+impl DeviceInfo {
+    fn new(ptr: ptr::NonNull<pw_sys::pw_device_info>) -> Self {
+        let props_ptr = unsafe { ptr.as_ref().props };
+        let props = ptr::NonNull::new(props_ptr).map(|ptr| unsafe { ForeignDict::from_ptr(ptr) });
+
+        Self { ptr, props }
+    }
+
+    pub fn id(&self) -> u32 {
+        unsafe { self.ptr.as_ref().id }
+    }
+
+    pub fn change_mask(&self) -> DeviceChangeMask {
+        let mask = unsafe { self.ptr.as_ref().change_mask };
+        DeviceChangeMask::from_bits(mask).expect("invalid change_mask")
+    }
+
+    pub fn props(&self) -> Option<&ForeignDict> {
+        self.props.as_ref()
+    }
+
+    /// Get the params supported by the device, which can be enumerated using [`Device::enum_params`].
+    pub fn params(&self) -> &[spa_sys::spa_param_info] {
+        unsafe {
+            let info = self.ptr.as_ref();
+            if info.params.is_null() {
+                &[]
+            } else {
+                std::slice::from_raw_parts(info.params, info.n_params as usize)
+            }
+        }
+    }
+}
+
+bitflags! {
+    pub struct DeviceChangeMask: u64 {
+        const PROPS = pw_sys::PW_DEVICE_CHANGE_MASK_PROPS as u64;
+        const PARAMS = pw_sys::PW_DEVICE_CHANGE_MASK_PARAMS as u64;
+    }
+}
+
+impl fmt::Debug for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeviceInfo")
+            .field("id", &self.id())
+            .field("change-mask", &self.change_mask())
+            .field("props", &self.props())
+            .field(
+                "params",
+                &self.params().iter().map(|p| p.id).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+pub struct DeviceListener {
+    // Need to stay allocated while the listener is registered
+    #[allow(dead_code)]
+    events: Pin<Box<pw_sys::pw_device_events>>,
+    listener: Pin<Box<spa_sys::spa_hook>>,
+    #[allow(dead_code)]
+    data: Box<ListenerLocalCallbacks>,
+    // Listener of the proxy, routing the errors about the device to the `error` callback
+    #[allow(dead_code)]
+    proxy_listener: Option<ProxyListener>,
+}
+
+impl Listener for DeviceListener {}
+
+impl Drop for DeviceListener {
+    fn drop(&mut self) {
+        spa::hook::remove(*self.listener);
+    }
+}
+
+impl<'a> DeviceListenerLocalBuilder<'a> {
+    #[must_use]
+    pub fn info<F>(mut self, info: F) -> Self
+    where
+        F: Fn(&DeviceInfo) + 'static,
+    {
+        self.cbs.info = Some(Box::new(info));
+        self
+    }
+
+    /// Set the callback called with the sequence number, the id, the index, the index of the next param
+    /// and the param itself for each param enumerated with [`enum_params`](`Device::enum_params`).
+    ///
+    /// The param is deserialized into an [`Object`], such as a `ParamProfile` or `ParamRoute` object,
+    /// and is `None` if it could not be deserialized.
+    #[must_use]
+    pub fn param<F>(mut self, param: F) -> Self
+    where
+        F: Fn(i32, u32, u32, u32, Option<&Object>) + 'static,
+    {
+        self.cbs.param = Some(Box::new(param));
+        self
+    }
+
+    /// Set the callback called with the sequence number, the negative `errno` and the message
+    /// of the errors reported by the server about the device, e.g. when a param set with
+    /// [`set_param`](`Device::set_param`) was rejected.
+    ///
+    /// See [`Error::from_core_error`] to convert the error into an [`Error`].
+    #[must_use]
+    pub fn error<F>(mut self, error: F) -> Self
+    where
+        F: Fn(i32, i32, &str) + 'static,
+    {
+        self.error = Some(Box::new(error));
+        self
+    }
+
+    #[must_use]
+    pub fn register(self) -> DeviceListener {
+        unsafe extern "C" fn device_events_info(
+            data: *mut c_void,
+            info: *const pw_sys::pw_device_info,
+        ) {
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let info = ptr::NonNull::new(info as *mut _).expect("info is NULL");
+                let info = DeviceInfo::new(info);
+                callbacks.info.as_ref().unwrap()(&info);
+            })
+        }
+
+        unsafe extern "C" fn device_events_param(
+            data: *mut c_void,
+            seq: i32,
+            id: u32,
+            index: u32,
+            next: u32,
+            param: *const spa_sys::spa_pod,
+        ) {
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let param = ptr::NonNull::new(param as *mut spa_sys::spa_pod).and_then(|param| {
+                    match PodDeserializer::deserialize_ptr::<Value>(param) {
+                        Ok(Value::Object(object)) => Some(object),
+                        _ => None,
+                    }
+                });
+                callbacks.param.as_ref().unwrap()(seq, id, index, next, param.as_ref());
+            })
+        }
+
+        let e = unsafe {
+            let mut e: Pin<Box<pw_sys::pw_device_events>> = Box::pin(mem::zeroed());
+            e.version = pw_sys::PW_VERSION_DEVICE_EVENTS;
+
+            if self.cbs.info.is_some() {
+                e.info = Some(device_events_info);
+            }
+            if self.cbs.param.is_some() {
+                e.param = Some(device_events_param);
+            }
+
+            e
+        };
+
+        let (listener, data) = unsafe {
+            let device = &self.device.proxy.as_ptr();
+
+            let data = Box::into_raw(Box::new(self.cbs));
+            let mut listener: Pin<Box<spa_sys::spa_hook>> = Box::pin(mem::zeroed());
+            let listener_ptr: *mut spa_sys::spa_hook = listener.as_mut().get_unchecked_mut();
+            let funcs: *const pw_sys::pw_device_events = e.as_ref().get_ref();
+
+            pw_sys::pw_proxy_add_object_listener(
+                device.cast(),
+                listener_ptr.cast(),
+                funcs.cast(),
+                data as *mut _,
+            );
+
+            (listener, Box::from_raw(data))
+        };
+
+        let proxy_listener = self.error.map(|error| {
+            self.device
+                .proxy
+                .add_listener_local()
+                .error(error)
+                .register()
+        });
+
+        DeviceListener {
+            events: e,
+            listener,
+            data,
+            proxy_listener,
+        }
+    }
+}
@@ -1,15 +1,93 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
+use spa::AsyncSeq;
 use thiserror::Error;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Creation failed")]
     CreationFailed,
+    /// Pipewire could not be initialized, e.g. because the library is not installed properly.
+    ///
+    /// Contains the `errno` reported by libpipewire.
+    #[error("Failed to initialize pipewire: {0}")]
+    InitializationFailed(errno::Errno),
+    /// Connecting to the pipewire daemon failed.
+    ///
+    /// Contains the `errno` reported by libpipewire.
+    /// If the daemon is not running, this is usually `ECONNREFUSED` or `ENOENT`
+    /// (see [`is_daemon_not_running`](`Self::is_daemon_not_running`)).
+    #[error("Failed to connect to pipewire: {0}")]
+    ConnectionFailed(errno::Errno),
     #[error("No memory")]
     NoMemory,
     #[error("Wrong proxy type")]
     WrongProxyType,
+    /// An error reported by the server through the `error` event of the core,
+    /// e.g. because an object could not be created.
+    ///
+    /// See [`from_core_error`](`Self::from_core_error`).
+    #[error("Error on object {id}: {message} ({errno})")]
+    ServerError {
+        /// The id of the object the error is about.
+        id: u32,
+        /// The sequence number of the request that caused the error.
+        seq: i32,
+        /// The `errno` of the error.
+        errno: errno::Errno,
+        /// The error message sent by the server.
+        message: String,
+    },
     #[error(transparent)]
     SpaError(#[from] spa::Error),
+    /// A pod provided by the application, such as a param, could not be serialized.
+    #[error(transparent)]
+    SerializationFailed(#[from] spa::pod::serialize::PodSerializeError),
+}
+
+impl Error {
+    /// Create an error from the arguments of the core `error` event.
+    ///
+    /// `res` is the negative `errno` reported by the server.
+    pub fn from_core_error(id: u32, seq: i32, res: i32, message: &str) -> Self {
+        Error::ServerError {
+            id,
+            seq,
+            errno: errno::Errno(res.saturating_neg()),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Convert the arguments of the core `error` event into an error if it is about the request with
+    /// the sequence number `expected`, such as the one returned by [`Core::sync`](`crate::Core::sync`).
+    ///
+    /// Returns `Ok(())` if the error is about another request.
+    ///
+    /// Errors about other objects, such as a failed creation, or about the connection itself
+    /// are reported with the sequence numbers of other requests and are thus not matched,
+    /// see [`Core::roundtrip_for`](`crate::CoreInner::roundtrip_for`) to wait for a request while catching them.
+    pub fn check_core_error(
+        expected: AsyncSeq,
+        id: u32,
+        seq: i32,
+        res: i32,
+        message: &str,
+    ) -> Result<(), Self> {
+        if seq == expected.raw() {
+            Err(Self::from_core_error(id, seq, res, message))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns `true` if the error was caused by the pipewire daemon not running or not being reachable.
+    pub fn is_daemon_not_running(&self) -> bool {
+        match self {
+            Error::ConnectionFailed(errno::Errno(e)) => {
+                *e == libc::ECONNREFUSED || *e == libc::ENOENT || *e == libc::EHOSTDOWN
+            }
+            _ => false,
+        }
+    }
 }
@@ -0,0 +1,649 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Pipewire Filter
+//!
+//! A filter is a node with any number of input and output ports, such as an equalizer
+//! processing stereo audio, which has two input ports and two output ports.
+//! Unlike a [`Stream`](`crate::stream::Stream`), which only has a single input or output,
+//! the buffers of each port of a filter are handled separately in the `process` callback.
+//!
+//! # Examples
+//! A filter copying its mono input to its output:
+//! ```no_run
+//! use pipewire::{
+//!     filter::{Filter, FilterFlags, Port, PortFlags},
+//!     properties, Context, MainLoop,
+//! };
+//!
+//! struct Ports<'f> {
+//!     input: Port<'f, ()>,
+//!     output: Port<'f, ()>,
+//! }
+//!
+//! let mainloop = MainLoop::new()?;
+//! let context = Context::new(&mainloop)?;
+//! let core = context.connect(None)?;
+//!
+//! let filter = Filter::new(
+//!     &core,
+//!     "copy",
+//!     properties! {
+//!         *pipewire::keys::MEDIA_TYPE => "Audio",
+//!         *pipewire::keys::MEDIA_CATEGORY => "Filter",
+//!         *pipewire::keys::MEDIA_ROLE => "DSP",
+//!     },
+//! )?;
+//!
+//! let dsp_port = |name: &str| {
+//!     properties! {
+//!         *pipewire::keys::FORMAT_DSP => "32 bit float mono audio",
+//!         *pipewire::keys::PORT_NAME => name,
+//!     }
+//! };
+//! let ports = Ports {
+//!     input: filter.add_port(
+//!         pipewire::spa::Direction::Input,
+//!         PortFlags::MAP_BUFFERS,
+//!         dsp_port("input"),
+//!         &mut [],
+//!         (),
+//!     )?,
+//!     output: filter.add_port(
+//!         pipewire::spa::Direction::Output,
+//!         PortFlags::MAP_BUFFERS,
+//!         dsp_port("output"),
+//!         &mut [],
+//!         (),
+//!     )?,
+//! };
+//!
+//! let _listener = filter
+//!     .add_local_listener_with_user_data(ports)
+//!     .process(|ports, position| {
+//!         let n_samples = match position {
+//!             Some(position) => position.duration() as u32,
+//!             None => return,
+//!         };
+//!         // Safety: the buffers hold the samples of the current cycle.
+//!         unsafe {
+//!             if let (Some(input), Some(output)) = (
+//!                 ports.input.dsp_buffer(n_samples),
+//!                 ports.output.dsp_buffer(n_samples),
+//!             ) {
+//!                 output.copy_from_slice(input);
+//!             }
+//!         }
+//!     })
+//!     .register()?;
+//!
+//! filter.connect(FilterFlags::RT_PROCESS, &mut [])?;
+//! mainloop.run();
+//! # Ok::<(), pipewire::Error>(())
+//! ```
+//!
+//! See `pipewire/examples/filter-eq.rs` in the crates repository for a filter processing stereo audio.
+
+use std::{
+    ffi::{CStr, CString},
+    marker::PhantomData,
+    mem, os,
+    pin::Pin,
+    ptr,
+};
+
+use bitflags::bitflags;
+use spa::result::SpaResult;
+
+use crate::{error::Error, stream::IoPosition, Core, Properties, PropertiesRef};
+
+#[derive(Debug)]
+pub enum FilterState {
+    Error(String),
+    Unconnected,
+    Connecting,
+    Paused,
+    Streaming,
+}
+
+impl FilterState {
+    pub(crate) fn from_raw(state: pw_sys::pw_filter_state, error: *const os::raw::c_char) -> Self {
+        match state {
+            pw_sys::pw_filter_state_PW_FILTER_STATE_UNCONNECTED => FilterState::Unconnected,
+            pw_sys::pw_filter_state_PW_FILTER_STATE_CONNECTING => FilterState::Connecting,
+            pw_sys::pw_filter_state_PW_FILTER_STATE_PAUSED => FilterState::Paused,
+            pw_sys::pw_filter_state_PW_FILTER_STATE_STREAMING => FilterState::Streaming,
+            _ => {
+                let error = if error.is_null() {
+                    "".to_string()
+                } else {
+                    unsafe { CStr::from_ptr(error).to_string_lossy().to_string() }
+                };
+
+                FilterState::Error(error)
+            }
+        }
+    }
+
+    /// Returns `true` if the filter is in the error state.
+    pub fn is_error(&self) -> bool {
+        matches!(self, FilterState::Error(_))
+    }
+}
+
+/// A wrapper around the pipewire filter interface.
+///
+/// The filter is destroyed when dropped, along with its ports.
+/// The [`Port`]s borrow the filter, so they can not outlive it.
+pub struct Filter {
+    ptr: ptr::NonNull<pw_sys::pw_filter>,
+    // the filter keeps a pointer on the core
+    _core: Core,
+}
+
+impl Filter {
+    /// Create a [`Filter`]
+    ///
+    /// Initialises a new filter with the given `name` and `properties`, without any port.
+    ///
+    /// # Panics
+    /// Will panic if `name` contains a 0 byte.
+    pub fn new(core: &Core, name: &str, properties: Properties) -> Result<Self, Error> {
+        let name = CString::new(name).expect("Invalid byte in filter name");
+        let filter =
+            unsafe { pw_sys::pw_filter_new(core.as_ptr(), name.as_ptr(), properties.into_raw()) };
+        let filter = ptr::NonNull::new(filter).ok_or(Error::CreationFailed)?;
+
+        Ok(Filter {
+            ptr: filter,
+            _core: core.clone(),
+        })
+    }
+
+    /// Add a port in the given `direction` to the filter, with the given `properties` and `params`.
+    ///
+    /// `data` is the user data of the port, which can be accessed from the returned [`Port`],
+    /// for example to keep the state of the processing of each channel.
+    ///
+    /// Ports of 32 bit float mono audio, whose buffers can be accessed using [`Port::dsp_buffer`],
+    /// are created by setting the [`FORMAT_DSP`](`crate::keys::FORMAT_DSP`) property to `"32 bit float mono audio"`.
+    ///
+    /// The port is removed from the filter when the returned [`Port`] is dropped.
+    pub fn add_port<P>(
+        &self,
+        direction: spa::Direction,
+        flags: PortFlags,
+        properties: Properties,
+        params: &mut [*const spa_sys::spa_pod],
+        data: P,
+    ) -> Result<Port<'_, P>, Error> {
+        let port = unsafe {
+            pw_sys::pw_filter_add_port(
+                self.as_ptr(),
+                direction.as_raw(),
+                flags.bits(),
+                // The user data is kept in the `Port` instead
+                0,
+                properties.into_raw(),
+                params.as_mut_ptr(),
+                params.len() as u32,
+            )
+        };
+        let port = ptr::NonNull::new(port).ok_or(Error::CreationFailed)?;
+
+        Ok(Port {
+            ptr: port,
+            data,
+            _filter: PhantomData,
+        })
+    }
+
+    /// Add a local listener builder, whose callbacks are called with `user_data`.
+    ///
+    /// The [`Port`]s of the filter are usually stored in the user data, so that they can be used
+    /// in the `process` callback.
+    #[must_use = "Fluent builder API"]
+    pub fn add_local_listener_with_user_data<D>(
+        &self,
+        user_data: D,
+    ) -> ListenerLocalBuilder<'_, D> {
+        ListenerLocalBuilder {
+            filter: self,
+            callbacks: ListenerLocalCallbacks::with_user_data(user_data),
+        }
+    }
+
+    /// Add a local listener builder
+    #[must_use = "Fluent builder API"]
+    pub fn add_local_listener<D: Default>(&self) -> ListenerLocalBuilder<'_, D> {
+        self.add_local_listener_with_user_data(Default::default())
+    }
+
+    /// Connect the filter
+    ///
+    /// The ports should be added before connecting the filter.
+    pub fn connect(
+        &self,
+        flags: FilterFlags,
+        params: &mut [*const spa_sys::spa_pod],
+    ) -> Result<(), Error> {
+        let r = crate::utils::resuming_panic(|| unsafe {
+            pw_sys::pw_filter_connect(
+                self.as_ptr(),
+                flags.bits(),
+                params.as_mut_ptr(),
+                params.len() as u32,
+            )
+        });
+
+        SpaResult::from_c(r).into_sync_result()?;
+        Ok(())
+    }
+
+    /// Disconnect the filter
+    pub fn disconnect(&self) -> Result<(), Error> {
+        let r =
+            crate::utils::resuming_panic(|| unsafe { pw_sys::pw_filter_disconnect(self.as_ptr()) });
+
+        SpaResult::from_c(r).into_sync_result()?;
+        Ok(())
+    }
+
+    /// Activate or deactivate the filter
+    pub fn set_active(&self, active: bool) -> Result<(), Error> {
+        let r = crate::utils::resuming_panic(|| unsafe {
+            pw_sys::pw_filter_set_active(self.as_ptr(), active)
+        });
+
+        SpaResult::from_c(r).into_sync_result()?;
+        Ok(())
+    }
+
+    /// Flush the filter. When `drain` is `true`, the `drained` callback will
+    /// be called when all data is processed.
+    pub fn flush(&self, drain: bool) -> Result<(), Error> {
+        let r = crate::utils::resuming_panic(|| unsafe {
+            pw_sys::pw_filter_flush(self.as_ptr(), drain)
+        });
+
+        SpaResult::from_c(r).into_sync_result()?;
+        Ok(())
+    }
+
+    fn as_ptr(&self) -> *mut pw_sys::pw_filter {
+        self.ptr.as_ptr()
+    }
+
+    // getters
+
+    /// Get the name of the filter.
+    pub fn name(&self) -> String {
+        let name = unsafe {
+            let name = pw_sys::pw_filter_get_name(self.as_ptr());
+            CStr::from_ptr(name)
+        };
+
+        name.to_string_lossy().to_string()
+    }
+
+    /// Get the current state of the filter.
+    pub fn state(&self) -> FilterState {
+        let mut error: *const os::raw::c_char = ptr::null();
+        let state =
+            unsafe { pw_sys::pw_filter_get_state(self.as_ptr(), (&mut error) as *mut *const _) };
+        FilterState::from_raw(state, error)
+    }
+
+    /// Get the properties of the filter.
+    pub fn properties(&self) -> PropertiesRef<'_> {
+        unsafe {
+            let props = pw_sys::pw_filter_get_properties(self.as_ptr(), ptr::null_mut());
+            let props = ptr::NonNull::new(props as *mut _).expect("filter properties is NULL");
+            PropertiesRef::from_ptr(props)
+        }
+    }
+
+    /// Get the node ID of the filter.
+    pub fn node_id(&self) -> u32 {
+        unsafe { pw_sys::pw_filter_get_node_id(self.as_ptr()) }
+    }
+}
+
+impl std::fmt::Debug for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Filter")
+            .field("name", &self.name())
+            .field("state", &self.state())
+            .field("node-id", &self.node_id())
+            .field("properties", &self.properties())
+            .finish()
+    }
+}
+
+impl Drop for Filter {
+    fn drop(&mut self) {
+        unsafe { pw_sys::pw_filter_destroy(self.as_ptr()) }
+    }
+}
+
+/// A port of a [`Filter`], created using [`Filter::add_port`].
+///
+/// `P` is the user data of the port.
+/// The port is removed from the filter when dropped.
+pub struct Port<'f, P> {
+    ptr: ptr::NonNull<os::raw::c_void>,
+    data: P,
+    _filter: PhantomData<&'f Filter>,
+}
+
+impl<'f, P> Port<'f, P> {
+    /// Get the user data of the port.
+    pub fn data(&self) -> &P {
+        &self.data
+    }
+
+    /// Get a mutable reference to the user data of the port.
+    pub fn data_mut(&mut self) -> &mut P {
+        &mut self.data
+    }
+
+    /// Get the pointer identifying the port in the `param_changed` callback.
+    pub fn as_ptr(&self) -> *mut os::raw::c_void {
+        self.ptr.as_ptr()
+    }
+
+    /// Get the samples of the current buffer of a 32 bit float mono audio port.
+    ///
+    /// For output ports, the buffer is marked as holding `n_samples` samples.
+    /// Returns `None` if no buffer is available.
+    ///
+    /// This does not allocate or lock, so it is safe to call from the `process` callback.
+    ///
+    /// # Safety
+    /// The port must have the 32 bit float mono audio DSP format, and `n_samples` must not exceed
+    /// the size of its buffers, such as the duration of the current cycle given by the position
+    /// passed to the `process` callback.
+    pub unsafe fn dsp_buffer(&mut self, n_samples: u32) -> Option<&mut [f32]> {
+        let buffer = pw_sys::pw_filter_get_dsp_buffer(self.as_ptr(), n_samples) as *mut f32;
+        if buffer.is_null() {
+            None
+        } else {
+            Some(std::slice::from_raw_parts_mut(buffer, n_samples as usize))
+        }
+    }
+
+    /// Take a buffer from the port.
+    ///
+    /// # Safety
+    ///
+    /// The pointer returned could be NULL if no buffer is available. The buffer
+    /// should be returned to the port once processing is complete.
+    pub unsafe fn dequeue_raw_buffer(&self) -> *mut pw_sys::pw_buffer {
+        pw_sys::pw_filter_dequeue_buffer(self.as_ptr())
+    }
+
+    /// Return a buffer to the port.
+    ///
+    /// # Safety
+    ///
+    /// The buffer pointer should be one obtained from this port by
+    /// a call to [`Port::dequeue_raw_buffer()`].
+    pub unsafe fn queue_raw_buffer(&self, buffer: *mut pw_sys::pw_buffer) {
+        pw_sys::pw_filter_queue_buffer(self.as_ptr(), buffer);
+    }
+}
+
+impl<'f, P> Drop for Port<'f, P> {
+    fn drop(&mut self) {
+        unsafe {
+            pw_sys::pw_filter_remove_port(self.as_ptr());
+        }
+    }
+}
+
+type ParamChangedCB<D> = dyn Fn(&mut D, *mut os::raw::c_void, u32, *const spa_sys::spa_pod);
+type ProcessCB<D> = dyn Fn(&mut D, Option<&IoPosition>);
+
+struct ListenerLocalCallbacks<D> {
+    state_changed: Option<Box<dyn Fn(FilterState, FilterState)>>,
+    param_changed: Option<Box<ParamChangedCB<D>>>,
+    process: Option<Box<ProcessCB<D>>>,
+    drained: Option<Box<dyn Fn()>>,
+    user_data: D,
+    filter: Option<ptr::NonNull<pw_sys::pw_filter>>,
+}
+
+impl<D> ListenerLocalCallbacks<D> {
+    fn with_user_data(user_data: D) -> Self {
+        ListenerLocalCallbacks {
+            state_changed: Default::default(),
+            param_changed: Default::default(),
+            process: Default::default(),
+            drained: Default::default(),
+            user_data,
+            filter: None,
+        }
+    }
+
+    fn into_raw(
+        self,
+    ) -> (
+        Pin<Box<pw_sys::pw_filter_events>>,
+        Box<ListenerLocalCallbacks<D>>,
+    ) {
+        let callbacks = Box::new(self);
+
+        unsafe extern "C" fn on_state_changed<D>(
+            data: *mut os::raw::c_void,
+            old: pw_sys::pw_filter_state,
+            new: pw_sys::pw_filter_state,
+            error: *const os::raw::c_char,
+        ) {
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.state_changed {
+                        let old = FilterState::from_raw(old, error);
+                        let new = FilterState::from_raw(new, error);
+                        cb(old, new)
+                    };
+                }
+            })
+        }
+
+        unsafe extern "C" fn on_param_changed<D>(
+            data: *mut os::raw::c_void,
+            port_data: *mut os::raw::c_void,
+            id: u32,
+            param: *const spa_sys::spa_pod,
+        ) {
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
+                    if let Some(ref cb) = state.param_changed {
+                        cb(&mut state.user_data, port_data, id, param);
+                    }
+                }
+            })
+        }
+
+        unsafe extern "C" fn on_process<D>(
+            data: *mut os::raw::c_void,
+            position: *mut spa_sys::spa_io_position,
+        ) {
+            crate::utils::catch_panic_or(
+                || {
+                    if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
+                        if let Some(ref cb) = state.process {
+                            let position = (position as *const IoPosition).as_ref();
+                            cb(&mut state.user_data, position);
+                        }
+                    }
+                },
+                || {
+                    // The panic is lost, e.g. with `RT_PROCESS` on the data loop thread,
+                    // so stop calling the callback and report the failure to the application.
+                    if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
+                        state.process = None;
+                        if let Some(ptr) = state.filter {
+                            let error = CString::new("Panic in the process callback").unwrap();
+                            pw_sys::pw_filter_set_error(ptr.as_ptr(), -libc::EIO, error.as_ptr());
+                        }
+                    }
+                },
+            )
+        }
+
+        unsafe extern "C" fn on_drained<D>(data: *mut os::raw::c_void) {
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.drained {
+                        cb();
+                    }
+                }
+            })
+        }
+
+        let events = unsafe {
+            let mut events: Pin<Box<pw_sys::pw_filter_events>> = Box::pin(mem::zeroed());
+            events.version = pw_sys::PW_VERSION_FILTER_EVENTS;
+
+            if callbacks.state_changed.is_some() {
+                events.state_changed = Some(on_state_changed::<D>);
+            }
+            if callbacks.param_changed.is_some() {
+                events.param_changed = Some(on_param_changed::<D>);
+            }
+            if callbacks.process.is_some() {
+                events.process = Some(on_process::<D>);
+            }
+            if callbacks.drained.is_some() {
+                events.drained = Some(on_drained::<D>);
+            }
+
+            events
+        };
+
+        (events, callbacks)
+    }
+}
+
+pub struct ListenerLocalBuilder<'a, D> {
+    filter: &'a Filter,
+    callbacks: ListenerLocalCallbacks<D>,
+}
+
+impl<'a, D> ListenerLocalBuilder<'a, D> {
+    /// Set the callback for the `state_changed` event.
+    #[must_use]
+    pub fn state_changed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(FilterState, FilterState) + 'static,
+    {
+        self.callbacks.state_changed = Some(Box::new(callback));
+        self
+    }
+
+    /// Set the callback for the `param_changed` event.
+    ///
+    /// The callback is called with the pointer of the port whose param changed, see [`Port::as_ptr`],
+    /// or a null pointer for the params of the filter itself.
+    #[must_use]
+    pub fn param_changed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut D, *mut os::raw::c_void, u32, *const spa_sys::spa_pod) + 'static,
+    {
+        self.callbacks.param_changed = Some(Box::new(callback));
+        self
+    }
+
+    /// Set the callback for the `process` event, called with the position of the graph
+    /// when the buffers of the ports of the filter have to be processed.
+    ///
+    /// If the callback panics, the panic is resumed from [`MainLoop::run`](`crate::MainLoopInner::run`)
+    /// if the callback is called by the main loop.
+    /// With [`RT_PROCESS`](`FilterFlags::RT_PROCESS`), the callback is called on the data loop thread
+    /// where the panic can not be resumed, so it is only logged, the filter is put in error state
+    /// and the callback is not called anymore.
+    #[must_use]
+    pub fn process<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut D, Option<&IoPosition>) + 'static,
+    {
+        self.callbacks.process = Some(Box::new(callback));
+        self
+    }
+
+    /// Set the callback for the `drained` event.
+    #[must_use]
+    pub fn drained<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + 'static,
+    {
+        self.callbacks.drained = Some(Box::new(callback));
+        self
+    }
+
+    //// Register the Callbacks
+    ///
+    /// Stop building the listener and register it on the filter. Returns a
+    /// `FilterListener` handle that will un-register the listener on drop.
+    pub fn register(self) -> Result<FilterListener<D>, Error> {
+        let (events, mut data) = self.callbacks.into_raw();
+        data.filter = Some(self.filter.ptr);
+        let (listener, data) = unsafe {
+            let listener: Box<spa_sys::spa_hook> = Box::new(mem::zeroed());
+            let raw_listener = Box::into_raw(listener);
+            let raw_data = Box::into_raw(data);
+            pw_sys::pw_filter_add_listener(
+                self.filter.as_ptr(),
+                raw_listener,
+                events.as_ref().get_ref(),
+                raw_data as *mut _,
+            );
+            (Box::from_raw(raw_listener), Box::from_raw(raw_data))
+        };
+        Ok(FilterListener {
+            listener,
+            _events: events,
+            _data: data,
+        })
+    }
+}
+
+pub struct FilterListener<D> {
+    listener: Box<spa_sys::spa_hook>,
+    // Need to stay allocated while the listener is registered
+    _events: Pin<Box<pw_sys::pw_filter_events>>,
+    _data: Box<ListenerLocalCallbacks<D>>,
+}
+
+impl<D> FilterListener<D> {
+    /// Stop the listener from receiving any events
+    ///
+    /// Removes the listener registration and cleans up allocated ressources.
+    pub fn unregister(self) {
+        // do nothing, drop will clean up.
+    }
+}
+
+impl<D> std::ops::Drop for FilterListener<D> {
+    fn drop(&mut self) {
+        spa::hook::remove(*self.listener);
+    }
+}
+
+bitflags! {
+    /// Extra flags that can be used in [`Filter::connect()`]
+    pub struct FilterFlags: pw_sys::pw_filter_flags {
+        const INACTIVE = pw_sys::pw_filter_flags_PW_FILTER_FLAG_INACTIVE;
+        const DRIVER = pw_sys::pw_filter_flags_PW_FILTER_FLAG_DRIVER;
+        const RT_PROCESS = pw_sys::pw_filter_flags_PW_FILTER_FLAG_RT_PROCESS;
+    }
+}
+
+bitflags! {
+    /// Extra flags that can be used in [`Filter::add_port()`]
+    pub struct PortFlags: pw_sys::pw_filter_port_flags {
+        const MAP_BUFFERS = pw_sys::pw_filter_port_flags_PW_FILTER_PORT_FLAG_MAP_BUFFERS;
+        const ALLOC_BUFFERS = pw_sys::pw_filter_port_flags_PW_FILTER_PORT_FLAG_ALLOC_BUFFERS;
+    }
+}
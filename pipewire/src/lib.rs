@@ -85,8 +85,8 @@
 //!     let mainloop = MainLoop::new()?;
 //!
 //!     let timer = mainloop.add_timer(|_| println!("Hello"));
-//!     // Call the first time in half a second, and then in a one second interval.
-//!     timer.update_timer(Some(Duration::from_millis(500)), Some(Duration::from_secs(1))).into_result()?;
+//!     // Call the first time in one second, and then in a one second interval.
+//!     timer.start_repeating(Duration::from_secs(1)).into_result()?;
 //!
 //!     mainloop.run();
 //!
@@ -122,11 +122,14 @@ pub use spa;
 
 pub mod buffer;
 pub mod channel;
+pub mod client;
 pub mod constants;
 mod context;
 mod core_;
 pub mod data;
+pub mod device;
 mod error;
+pub mod filter;
 pub mod keys;
 pub mod link;
 mod loop_;
@@ -160,8 +163,59 @@ pub fn init() {
     INITIALIZED.get_or_init(|| unsafe { pw_sys::pw_init(ptr::null_mut(), ptr::null_mut()) });
 }
 
+/// Initialize PipeWire, returning a guard deinitializing it once dropped.
+///
+/// This is a safe alternative to calling [`init`] and [`deinit`] manually.
+/// The guard should be kept alive until all PipeWire objects have been dropped.
+/// As local variables are dropped in reverse order of their declaration, this can be achieved by binding the guard
+/// at the beginning of `main`:
+/// ```no_run
+/// fn main() -> Result<(), pipewire::Error> {
+///     let _guard = pipewire::init_guarded();
+///
+///     let mainloop = pipewire::MainLoop::new()?;
+///     mainloop.run();
+///
+///     // `mainloop` is dropped before `_guard` deinitializes PipeWire.
+///     Ok(())
+/// }
+/// ```
+///
+/// # Panics
+/// PipeWire can only be deinitialized once during the lifetime of the process,
+/// so this panics if it is called more than once.
+pub fn init_guarded() -> PipewireGuard {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static GUARDED: AtomicBool = AtomicBool::new(false);
+
+    if GUARDED.swap(true, Ordering::SeqCst) {
+        panic!("init_guarded() must only be called once");
+    }
+
+    init();
+    PipewireGuard {
+        _not_send: std::marker::PhantomData,
+    }
+}
+
+/// A guard deinitializing PipeWire when dropped, returned by [`init_guarded`].
+#[must_use = "PipeWire is deinitialized as soon as the guard is dropped"]
+pub struct PipewireGuard {
+    // Deinitialize PipeWire on the thread that initialized it.
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl Drop for PipewireGuard {
+    fn drop(&mut self) {
+        // Only one guard can be created, so this is only called once.
+        unsafe { deinit() }
+    }
+}
+
 /// Deinitialize PipeWire
 ///
+/// See [`init_guarded`] for a safe alternative.
+///
 /// # Safety
 /// This must only be called once during the lifetime of the process, once no PipeWire threads
 /// are running anymore and all PipeWire resources are released.
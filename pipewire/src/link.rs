@@ -14,10 +14,13 @@ use crate::{
 };
 
 #[derive(Debug)]
+#[repr(transparent)]
 pub struct Link {
     proxy: Proxy,
 }
 
+impl crate::proxy::sealed::Sealed for Link {}
+
 impl ProxyT for Link {
     fn type_() -> ObjectType {
         ObjectType::Link
@@ -37,6 +40,14 @@ impl ProxyT for Link {
     {
         Self { proxy }
     }
+
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized,
+    {
+        // Safety: `Self` is a `#[repr(transparent)]` wrapper around a `Proxy`.
+        &*(proxy as *const Proxy as *const Self)
+    }
 }
 
 impl Link {
@@ -92,9 +103,11 @@ impl<'a> LinkListenerLocalBuilder<'a> {
             data: *mut c_void,
             info: *const pw_sys::pw_link_info,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let info = LinkInfo::new(ptr::NonNull::new(info as *mut _).expect("info is NULL"));
-            callbacks.info.as_ref().unwrap()(&info);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let info = LinkInfo::new(ptr::NonNull::new(info as *mut _).expect("info is NULL"));
+                callbacks.info.as_ref().unwrap()(&info);
+            })
         }
 
         let e = unsafe {
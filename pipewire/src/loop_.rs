@@ -1,22 +1,52 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::{convert::TryInto, os::unix::prelude::*, ptr, time::Duration};
+use std::{
+    convert::TryInto,
+    marker::PhantomData,
+    os::unix::{io::BorrowedFd, prelude::*},
+    ptr,
+    time::Duration,
+};
 
 use libc::{c_int, c_void};
 use signal::Signal;
 use spa::{flags::IoFlags, result::SpaResult, spa_interface_call_method};
 
-use crate::utils::assert_main_thread;
+use crate::{utils::assert_main_thread, Error};
 
 /// A trait for common functionality of the different pipewire loop kinds, most notably [`MainLoop`](`crate::MainLoop`).
 ///
 /// Different kinds of events, such as receiving a signal (e.g. SIGTERM) can be attached to the loop using this trait.
+///
+/// # Sources
+/// Each event source returned by the methods of this trait borrows the loop it has been attached to,
+/// as it is destroyed through the loop when dropped.
+/// This ensures that the loop can not be dropped, or moved, while one of its sources is alive:
+/// ```compile_fail
+/// use pipewire::{prelude::*, MainLoop};
+///
+/// let mainloop = MainLoop::new()?;
+/// let event = mainloop.add_event(|| {});
+/// drop(mainloop);
+/// event.signal();
+/// # Ok::<(), pipewire::Error>(())
+/// ```
+///
+/// For the same reason, a source can not be stored in a struct along with the loop it has been attached to.
+/// Keep the loop outside of the struct instead, so that the struct borrows it for as long as it holds the source.
 pub trait Loop {
     /// # Safety
     /// The returned pointer must not be null, and must point to a valid, well-aligned `pw_loop`.
+    ///
+    /// The `pw_loop` must stay valid as long as `self` is alive, since the sources attached to the loop
+    /// only borrow `self` and use the pointer to destroy themselves when dropped.
     unsafe fn as_ptr(&self) -> *mut pw_sys::pw_loop;
 
+    /// Call `callback` with `io` when its file descriptor is ready for one of the events in `event_mask`.
+    ///
+    /// The loop never closes the file descriptor, `io` is dropped along with the returned [`IoSource`].
+    /// To watch a file descriptor that is owned elsewhere, use [`add_io_fd`](`Self::add_io_fd`).
     #[must_use]
     fn add_io<I, F>(&self, io: I, event_mask: IoFlags, callback: F) -> IoSource<I, Self>
     where
@@ -28,8 +58,10 @@ pub trait Loop {
         where
             I: AsRawFd,
         {
-            let (io, callback) = (data as *mut IoSourceData<I>).as_mut().unwrap();
-            callback(io);
+            crate::utils::catch_panic(|| {
+                let (io, callback) = (data as *mut IoSourceData<I>).as_mut().unwrap();
+                callback(io);
+            })
         }
 
         let fd = io.as_raw_fd();
@@ -70,6 +102,41 @@ pub trait Loop {
         }
     }
 
+    /// Call `callback` when `fd` is ready for one of the events in `event_mask`.
+    ///
+    /// The loop only borrows the file descriptor, which has to stay open as long as the returned [`IoSource`]
+    /// is alive, and is not closed by the loop.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use pipewire::{prelude::*, spa::flags::IoFlags, MainLoop};
+    /// use std::os::unix::{io::AsFd, net::UnixStream};
+    ///
+    /// let mainloop = MainLoop::new()?;
+    /// let (socket, _other) = UnixStream::pair().unwrap();
+    ///
+    /// let _source = mainloop.add_io_fd(socket.as_fd(), IoFlags::IN, |_fd| {
+    ///     println!("The socket is readable");
+    /// });
+    /// // `socket` can not be dropped before `_source`.
+    /// # Ok::<(), pipewire::Error>(())
+    /// ```
+    #[must_use]
+    fn add_io_fd<'fd, F>(
+        &self,
+        fd: BorrowedFd<'fd>,
+        event_mask: IoFlags,
+        callback: F,
+    ) -> IoSource<BorrowedFd<'fd>, Self>
+    where
+        F: Fn(BorrowedFd<'fd>) + 'static,
+        Self: Sized,
+    {
+        self.add_io(fd, event_mask, move |fd: &mut BorrowedFd<'fd>| {
+            callback(*fd)
+        })
+    }
+
     #[must_use]
     fn add_signal_local<F>(&self, signal: Signal, callback: F) -> SignalSource<Self>
     where
@@ -82,8 +149,10 @@ pub trait Loop {
         where
             F: Fn(),
         {
-            let callback = (data as *mut F).as_ref().unwrap();
-            callback();
+            crate::utils::catch_panic(|| {
+                let callback = (data as *mut F).as_ref().unwrap();
+                callback();
+            })
         }
 
         let data = Box::into_raw(Box::new(callback));
@@ -119,21 +188,106 @@ pub trait Loop {
         }
     }
 
+    /// Get the file descriptor of the loop.
+    ///
+    /// The file descriptor becomes readable when the loop has pending work,
+    /// so it can be added to another event loop, which then calls [`dispatch`](`Self::dispatch`)
+    /// when it is readable.
+    fn fd(&self) -> RawFd {
+        unsafe {
+            let mut iface = self
+                .as_ptr()
+                .as_ref()
+                .unwrap()
+                .control
+                .as_ref()
+                .unwrap()
+                .iface;
+
+            spa_interface_call_method!(
+                &mut iface as *mut spa_sys::spa_interface,
+                spa_sys::spa_loop_control_methods,
+                get_fd,
+            )
+        }
+    }
+
+    /// Run a single iteration of the loop, dispatching the sources that are ready.
+    ///
+    /// This waits for up to `timeout` for a source to become ready, or indefinitely if `timeout` is `None`.
+    /// Pass `Some(Duration::ZERO)` to only dispatch the sources that are ready without blocking.
+    ///
+    /// The loop is entered and left again around the iteration, so this must not be called while the loop is
+    /// already running, e.g. from a callback called by [`MainLoop::run`](`crate::MainLoop::run`).
+    ///
+    /// If a callback panics, the panic is resumed from this method once the iteration is done.
+    ///
+    /// Returns the number of dispatched sources, which is zero if the timeout expired.
+    fn dispatch(&self, timeout: Option<Duration>) -> Result<u32, Error> {
+        let timeout: c_int = timeout.map_or(-1, |timeout| {
+            timeout.as_millis().try_into().unwrap_or(c_int::MAX)
+        });
+
+        let res = crate::utils::resuming_panic(|| unsafe {
+            let mut iface = self
+                .as_ptr()
+                .as_ref()
+                .unwrap()
+                .control
+                .as_ref()
+                .unwrap()
+                .iface;
+            let iface = &mut iface as *mut spa_sys::spa_interface;
+
+            spa_interface_call_method!(iface, spa_sys::spa_loop_control_methods, enter,);
+            let res = spa_interface_call_method!(
+                iface,
+                spa_sys::spa_loop_control_methods,
+                iterate,
+                timeout
+            );
+            spa_interface_call_method!(iface, spa_sys::spa_loop_control_methods, leave,);
+
+            res
+        });
+
+        let dispatched = SpaResult::from_c(res).into_sync_result()?;
+        Ok(dispatched as u32)
+    }
+
     /// Register a new event with a callback to be called when the event happens.
     ///
     /// The returned [`EventSource`] can be used to trigger the event.
+    ///
+    /// Events do not carry any data, use the [`channel`](`crate::channel`) module to send data to the loop.
     #[must_use]
     fn add_event<F>(&self, callback: F) -> EventSource<Self>
     where
         F: Fn() + 'static,
         Self: Sized,
     {
-        unsafe extern "C" fn call_closure<F>(data: *mut c_void, _count: u64)
+        self.add_event_with_count(move |_| callback())
+    }
+
+    /// Variant of [`add_event`](`Self::add_event`) whose callback is provided with the number of times
+    /// the event has been signaled since the callback was last called.
+    ///
+    /// This is useful when the event is signaled more often than the loop can handle it,
+    /// as the signals are then coalesced into a single call of the callback.
+    #[must_use]
+    fn add_event_with_count<F>(&self, callback: F) -> EventSource<Self>
+    where
+        F: Fn(u64) + 'static,
+        Self: Sized,
+    {
+        unsafe extern "C" fn call_closure<F>(data: *mut c_void, count: u64)
         where
-            F: Fn(),
+            F: Fn(u64),
         {
-            let callback = (data as *mut F).as_ref().unwrap();
-            callback();
+            crate::utils::catch_panic(|| {
+                let callback = (data as *mut F).as_ref().unwrap();
+                callback(count);
+            })
         }
 
         let data = Box::into_raw(Box::new(callback));
@@ -182,8 +336,10 @@ pub trait Loop {
         where
             F: Fn(u64),
         {
-            let callback = (data as *mut F).as_ref().unwrap();
-            callback(expirations);
+            crate::utils::catch_panic(|| {
+                let callback = (data as *mut F).as_ref().unwrap();
+                callback(expirations);
+            })
         }
 
         let data = Box::into_raw(Box::new(callback));
@@ -242,6 +398,41 @@ pub trait Loop {
     }
 }
 
+/// A borrowed `pw_loop` that has been created outside of this crate, for example by a plugin host.
+///
+/// The loop is not owned by this struct and will not be destroyed when it is dropped,
+/// but all the functionality of the [`Loop`] trait can be used with it.
+#[derive(Debug, Clone, Copy)]
+pub struct LoopRef<'a> {
+    ptr: ptr::NonNull<pw_sys::pw_loop>,
+    // ensure that LoopRef does not outlive the loop it was created from
+    _phantom: PhantomData<&'a pw_sys::pw_loop>,
+}
+
+impl<'a> LoopRef<'a> {
+    /// Create a [`LoopRef`] from an existing raw `pw_loop` pointer.
+    ///
+    /// # Safety
+    /// - The provided pointer must point to a valid, well-aligned `pw_loop` struct.
+    /// - The generated `LoopRef` will not take ownership of the pointer so the
+    ///   `pw_loop` struct has to stay alive during all its lifetime.
+    ///
+    /// # Panics
+    /// Panics if `ptr` is null.
+    pub unsafe fn from_raw(ptr: *mut pw_sys::pw_loop) -> Self {
+        Self {
+            ptr: ptr::NonNull::new(ptr).expect("pw_loop pointer is NULL"),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> Loop for LoopRef<'a> {
+    unsafe fn as_ptr(&self) -> *mut pw_sys::pw_loop {
+        self.ptr.as_ptr()
+    }
+}
+
 pub trait IsASource {
     /// Return a valid pointer to a raw `spa_source`.
     fn as_ptr(&self) -> *mut spa_sys::spa_source;
@@ -309,7 +500,8 @@ where
 
 /// A source that can be used to signal to a loop that an event has occurred.
 ///
-/// This source can be obtained by calling [`add_event`](`Loop::add_event`) on a loop, registering a callback to it.
+/// This source can be obtained by calling [`add_event`](`Loop::add_event`) or
+/// [`add_event_with_count`](`Loop::add_event_with_count`) on a loop, registering a callback to it.
 /// By calling [`signal`](`EventSource::signal`) on the `EventSource`, the loop is signaled that the event has occurred.
 /// It will then call the callback at the next possible occasion.
 pub struct EventSource<'a, L>
@@ -319,7 +511,7 @@ where
     ptr: ptr::NonNull<spa_sys::spa_source>,
     loop_: &'a L,
     // Store data wrapper to prevent leak
-    _data: Box<dyn Fn() + 'static>,
+    _data: Box<dyn Fn(u64) + 'static>,
 }
 
 impl<'a, L> IsASource for EventSource<'a, L>
@@ -375,7 +567,12 @@ where
 /// This source can be obtained by calling [`add_timer`](`Loop::add_timer`) on a loop, registering a callback to it.
 ///
 /// The timer starts out inactive.
-/// You can arm or disarm the timer by calling [`update_timer`](`Self::update_timer`).
+/// You can arm or disarm the timer by calling [`update_timer`](`Self::update_timer`),
+/// arm it using [`start_oneshot`](`Self::start_oneshot`) or [`start_repeating`](`Self::start_repeating`),
+/// or disarm it using [`disarm`](`Self::disarm`).
+///
+/// Dropping the source destroys the timer, so the source must be kept alive as long as the timer
+/// may be armed again. To only stop the timer, disarm it instead.
 pub struct TimerSource<'a, L>
 where
     L: Loop,
@@ -435,6 +632,36 @@ where
 
         SpaResult::from_c(res)
     }
+
+    /// Arm the timer to be called once, after `delay`.
+    ///
+    /// Unlike with [`update_timer`](`Self::update_timer`), a zero `delay` does not disarm the timer,
+    /// but makes it be called as soon as possible.
+    ///
+    /// # Panics
+    /// The provided durations seconds must fit in an i64. Otherwise, this function will panic.
+    pub fn start_oneshot(&self, delay: Duration) -> SpaResult {
+        self.update_timer(Some(delay.max(Duration::from_nanos(1))), None)
+    }
+
+    /// Arm the timer to be called repeatedly, every `interval`, starting after one `interval` has elapsed.
+    ///
+    /// If `interval` is zero, the timer will be called only once, as soon as possible.
+    ///
+    /// # Panics
+    /// The provided durations seconds must fit in an i64. Otherwise, this function will panic.
+    pub fn start_repeating(&self, interval: Duration) -> SpaResult {
+        self.update_timer(Some(interval.max(Duration::from_nanos(1))), Some(interval))
+    }
+
+    /// Disarm the timer, so that its callback is not called anymore until it is armed again.
+    ///
+    /// This is the same as calling [`update_timer`](`Self::update_timer`) with `None` durations.
+    /// Unlike dropping the source, which destroys the timer, the timer stays attached to the loop
+    /// and can be armed again later.
+    pub fn disarm(&self) -> SpaResult {
+        self.update_timer(None, None)
+    }
 }
 
 impl<'a, L> IsASource for TimerSource<'a, L>
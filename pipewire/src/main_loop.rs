@@ -1,11 +1,16 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
+use std::cell::Cell;
+#[cfg(feature = "v1_1")]
+use std::ffi::CStr;
 use std::ops::Deref;
 use std::ptr;
 use std::rc::{Rc, Weak};
+use std::time::Duration;
 
 use crate::loop_::Loop;
+use crate::utils;
 use crate::{error::Error, Properties};
 use spa::ReadableDict;
 
@@ -16,6 +21,8 @@ pub struct MainLoop {
 
 impl MainLoop {
     /// Initialize Pipewire and create a new `MainLoop`
+    ///
+    /// Returns [`Error::InitializationFailed`] with the reported `errno` if pipewire could not be initialized.
     pub fn new() -> Result<Self, Error> {
         super::init();
         let inner = MainLoopInner::new::<Properties>(None)?;
@@ -24,17 +31,63 @@ impl MainLoop {
         })
     }
 
+    /// Initialize Pipewire and create a new `MainLoop` using the provided properties.
+    ///
+    /// The properties can be used to select the support libraries used by the loop, using
+    /// [`LIBRARY_NAME_SYSTEM`](`crate::keys::LIBRARY_NAME_SYSTEM`) and
+    /// [`LIBRARY_NAME_LOOP`](`crate::keys::LIBRARY_NAME_LOOP`).
+    ///
+    /// Returns [`Error::InitializationFailed`] if a library could not be loaded.
     pub fn with_properties<T: ReadableDict>(properties: &T) -> Result<Self, Error> {
+        super::init();
         let inner = MainLoopInner::new(Some(properties))?;
         Ok(Self {
             inner: Rc::new(inner),
         })
     }
 
+    /// Get the name of the loop, which can be set using the `loop.name` property
+    /// when creating the loop with [`with_properties`](`Self::with_properties`).
+    ///
+    /// This is useful to tell loops apart in logs when several loops are running.
+    ///
+    /// Returns `None` if the loop has no name.
+    #[cfg(feature = "v1_1")]
+    pub fn name(&self) -> Option<String> {
+        unsafe {
+            let name = (*Loop::as_ptr(self)).name;
+            if name.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+            }
+        }
+    }
+
     pub fn downgrade(&self) -> WeakMainLoop {
         let weak = Rc::downgrade(&self.inner);
         WeakMainLoop { weak }
     }
+
+    /// Run the loop for the provided `duration`, then return.
+    ///
+    /// This arms a timer on the loop that calls [`quit`](`MainLoopInner::quit`) once the duration elapsed.
+    /// The loop may return earlier if `quit` is called by any other event in the meantime.
+    pub fn run_for(&self, duration: Duration) {
+        let weak = self.downgrade();
+        let timer = self.add_timer(move |_| {
+            if let Some(mainloop) = weak.upgrade() {
+                mainloop.quit();
+            }
+        });
+
+        timer
+            .start_oneshot(duration)
+            .into_sync_result()
+            .expect("Failed to arm timer");
+
+        self.run();
+    }
 }
 
 impl Deref for MainLoop {
@@ -64,6 +117,10 @@ impl WeakMainLoop {
 #[derive(Debug)]
 pub struct MainLoopInner {
     ptr: ptr::NonNull<pw_sys::pw_main_loop>,
+    /// Whether [`run`](`Self::run`) is currently running the loop.
+    running: Cell<bool>,
+    /// Whether [`quit`](`Self::quit`) was called while the loop was not running.
+    quit_pending: Cell<bool>,
 }
 
 impl MainLoopInner {
@@ -71,9 +128,14 @@ impl MainLoopInner {
         unsafe {
             let props = properties.map_or(ptr::null(), |props| props.get_dict_ptr()) as *mut _;
             let l = pw_sys::pw_main_loop_new(props);
-            let ptr = ptr::NonNull::new(l).ok_or(Error::CreationFailed)?;
+            let ptr =
+                ptr::NonNull::new(l).ok_or_else(|| Error::InitializationFailed(errno::errno()))?;
 
-            Ok(MainLoopInner { ptr })
+            Ok(MainLoopInner {
+                ptr,
+                running: Cell::new(false),
+                quit_pending: Cell::new(false),
+            })
         }
     }
 
@@ -81,17 +143,69 @@ impl MainLoopInner {
         self.ptr.as_ptr()
     }
 
+    /// Get the raw `pw_main_loop` pointer, to call functions of libpipewire that are not bound yet.
+    ///
+    /// The underlying `pw_loop` can be obtained using [`Loop::as_ptr`].
+    ///
+    /// # Safety
+    /// The pointer is only valid for the lifetime of the loop, and ownership of the `pw_main_loop`
+    /// is not transferred to the caller, so it must not be destroyed.
+    /// The loop must not be run or quit behind the back of [`run`](`Self::run`) and [`quit`](`Self::quit`).
+    pub unsafe fn as_raw(&self) -> *mut pw_sys::pw_main_loop {
+        self.as_ptr()
+    }
+
+    /// Run the loop until [`quit`](`Self::quit`) is called.
+    ///
+    /// If `quit` has been called since the loop last stopped running, this returns immediately instead.
+    ///
+    /// # Panics
+    /// If the loop is already running, e.g. if this is called from a callback of the loop.
+    ///
+    /// If a callback panics while the loop is running, the loop stops and the panic is resumed from here,
+    /// as unwinding through the C code of libpipewire is not possible.
+    /// Callbacks called on other threads, such as the `process` callback of a stream
+    /// with [`RT_PROCESS`](`crate::stream::StreamFlags::RT_PROCESS`), are not called by the loop,
+    /// so their panics are only logged.
     pub fn run(&self) {
-        unsafe {
-            pw_sys::pw_main_loop_run(self.as_ptr());
+        assert!(
+            !self.running.get(),
+            "MainLoop::run() called while the loop is already running"
+        );
+
+        if self.quit_pending.replace(false) {
+            return;
         }
+
+        self.running.set(true);
+        utils::running_main_loop(self.as_ptr(), || unsafe {
+            pw_sys::pw_main_loop_run(self.as_ptr());
+        });
+        self.running.set(false);
+
+        utils::resume_panic();
     }
 
+    /// Make the loop stop running.
+    ///
+    /// If the loop is not running, the next call to [`run`](`Self::run`) will return immediately instead,
+    /// so that the request to quit is not lost if it is made before the loop starts running.
+    /// Calling this several times before the loop runs has the same effect as calling it once.
     pub fn quit(&self) {
+        if !self.running.get() {
+            self.quit_pending.set(true);
+            return;
+        }
+
         unsafe {
             pw_sys::pw_main_loop_quit(self.as_ptr());
         }
     }
+
+    /// Returns `true` if the loop is currently running, i.e. if this is called from within [`run`](`Self::run`).
+    pub fn is_running(&self) -> bool {
+        self.running.get()
+    }
 }
 
 impl Drop for MainLoopInner {
@@ -99,3 +213,135 @@ impl Drop for MainLoopInner {
         unsafe { pw_sys::pw_main_loop_destroy(self.ptr.as_ptr()) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn run_for() {
+        let mainloop = MainLoop::new().unwrap();
+
+        let start = Instant::now();
+        mainloop.run_for(Duration::from_millis(10));
+        assert!(start.elapsed() >= Duration::from_millis(10));
+
+        // A zero duration must not block forever.
+        mainloop.run_for(Duration::ZERO);
+    }
+
+    #[test]
+    fn with_properties_library_name() {
+        let props = crate::properties! {
+            *crate::keys::LIBRARY_NAME_SYSTEM => "support/does-not-exist",
+        };
+
+        assert!(matches!(
+            MainLoop::with_properties(&props),
+            Err(Error::InitializationFailed(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "v1_1")]
+    fn name() {
+        let props = crate::properties! {
+            "loop.name" => "test-loop",
+        };
+
+        let mainloop = MainLoop::with_properties(&props).unwrap();
+        assert_eq!(mainloop.name().as_deref(), Some("test-loop"));
+    }
+
+    #[test]
+    fn quit_before_run() {
+        let mainloop = MainLoop::new().unwrap();
+        assert!(!mainloop.is_running());
+
+        // The pending quit makes `run` return immediately, only once.
+        mainloop.quit();
+        mainloop.quit();
+        mainloop.run();
+        assert!(!mainloop.is_running());
+
+        let weak = mainloop.downgrade();
+        let running = Rc::new(Cell::new(false));
+        let timer = mainloop.add_timer({
+            let running = running.clone();
+            move |_| {
+                let mainloop = weak.upgrade().unwrap();
+                running.set(mainloop.is_running());
+                mainloop.quit();
+            }
+        });
+        timer
+            .start_oneshot(Duration::from_millis(1))
+            .into_sync_result()
+            .unwrap();
+        mainloop.run();
+
+        assert!(running.get());
+        assert!(!mainloop.is_running());
+    }
+
+    #[test]
+    fn panic_in_callback() {
+        let mainloop = MainLoop::new().unwrap();
+
+        let timer = mainloop.add_timer(|_| panic!("panic in timer"));
+        timer
+            .start_oneshot(Duration::from_millis(1))
+            .into_sync_result()
+            .unwrap();
+
+        // The panic stops the loop and is resumed from `run`.
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| mainloop.run()))
+            .expect_err("the panic was not resumed");
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"panic in timer"));
+        assert!(!mainloop.is_running());
+
+        // The loop can be run again.
+        timer.disarm().into_sync_result().unwrap();
+        mainloop.run_for(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn panic_in_callback_dispatch() {
+        let mainloop = MainLoop::new().unwrap();
+
+        let timer = mainloop.add_timer(|_| panic!("panic in timer"));
+        timer
+            .start_oneshot(Duration::from_millis(1))
+            .into_sync_result()
+            .unwrap();
+
+        // The panic is resumed from `dispatch` instead of being kept for a later call to `run`.
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mainloop.dispatch(Some(Duration::from_secs(1)))
+        }))
+        .expect_err("the panic was not resumed");
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"panic in timer"));
+
+        // No panic is left to be resumed by `run`.
+        mainloop.run_for(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn event_count() {
+        let mainloop = MainLoop::new().unwrap();
+
+        let count = Rc::new(std::cell::Cell::new(0));
+        let event = mainloop.add_event_with_count({
+            let count = count.clone();
+            move |n| count.set(count.get() + n)
+        });
+
+        // Signals sent before the loop gets to handle them are coalesced.
+        event.signal().into_sync_result().unwrap();
+        event.signal().into_sync_result().unwrap();
+        mainloop.dispatch(Some(Duration::ZERO)).unwrap();
+
+        assert_eq!(count.get(), 2);
+    }
+}
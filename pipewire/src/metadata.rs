@@ -14,10 +14,13 @@ use crate::{
 };
 
 #[derive(Debug)]
+#[repr(transparent)]
 pub struct Metadata {
     proxy: Proxy,
 }
 
+impl crate::proxy::sealed::Sealed for Metadata {}
+
 impl ProxyT for Metadata {
     fn type_() -> ObjectType {
         ObjectType::Metadata
@@ -37,6 +40,14 @@ impl ProxyT for Metadata {
     {
         Self { proxy }
     }
+
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized,
+    {
+        // Safety: `Self` is a `#[repr(transparent)]` wrapper around a `Proxy`.
+        &*(proxy as *const Proxy as *const Self)
+    }
 }
 
 impl Metadata {
@@ -95,15 +106,17 @@ impl<'meta> MetadataListenerLocalBuilder<'meta> {
             type_: *const c_char,
             value: *const c_char,
         ) -> i32 {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let key = CStr::from_ptr(key).to_string_lossy();
-            let type_ = if !type_.is_null() {
-                Some(CStr::from_ptr(type_).to_string_lossy())
-            } else {
-                None
-            };
-            let value = CStr::from_ptr(value).to_string_lossy();
-            callbacks.property.as_ref().unwrap()(subject, &key, type_.as_deref(), &value)
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let key = CStr::from_ptr(key).to_string_lossy();
+                let type_ = if !type_.is_null() {
+                    Some(CStr::from_ptr(type_).to_string_lossy())
+                } else {
+                    None
+                };
+                let value = CStr::from_ptr(value).to_string_lossy();
+                callbacks.property.as_ref().unwrap()(subject, &key, type_.as_deref(), &value)
+            })
         }
 
         let e = unsafe {
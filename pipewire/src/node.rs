@@ -4,20 +4,28 @@
 use bitflags::bitflags;
 use libc::c_void;
 use std::pin::Pin;
-use std::{ffi::CStr, ptr};
+use std::{convert::TryInto, ffi::CStr, io::Cursor, ptr};
 use std::{fmt, mem};
 
 use crate::{
-    proxy::{Listener, Proxy, ProxyT},
+    proxy::{Listener, Proxy, ProxyListener, ProxyT},
     types::ObjectType,
+    Error,
+};
+use spa::{
+    dict::ForeignDict,
+    pod::{serialize::PodSerializer, Object, Value},
+    spa_interface_call_method,
 };
-use spa::dict::ForeignDict;
 
 #[derive(Debug)]
+#[repr(transparent)]
 pub struct Node {
     proxy: Proxy,
 }
 
+impl crate::proxy::sealed::Sealed for Node {}
+
 impl ProxyT for Node {
     fn type_() -> ObjectType {
         ObjectType::Node
@@ -37,6 +45,14 @@ impl ProxyT for Node {
     {
         Self { proxy }
     }
+
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized,
+    {
+        // Safety: `Self` is a `#[repr(transparent)]` wrapper around a `Proxy`.
+        &*(proxy as *const Proxy as *const Self)
+    }
 }
 
 impl Node {
@@ -46,6 +62,87 @@ impl Node {
         NodeListenerLocalBuilder {
             node: self,
             cbs: ListenerLocalCallbacks::default(),
+            error: None,
+        }
+    }
+
+    /// Send a command to the node, e.g. to start or suspend it.
+    ///
+    /// This requires the client to have the `X` [`Permission`](`crate::registry::Permission`) on the node.
+    pub fn send_command(&self, command: NodeCommand) -> Result<(), Error> {
+        // Commands are objects without properties, with the command as their id.
+        let command = Value::Object(Object {
+            type_: spa_sys::SPA_TYPE_COMMAND_Node,
+            id: command.as_raw(),
+            properties: Vec::new(),
+        });
+        let (command, _) = PodSerializer::serialize(Cursor::new(Vec::new()), &command)
+            .expect("Failed to serialize command");
+        let command = command.into_inner();
+
+        let res = unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr() as *mut pw_sys::pw_node,
+                pw_sys::pw_node_methods,
+                send_command,
+                command.as_ptr() as *const spa_sys::spa_command
+            )
+        };
+
+        spa::SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+
+    /// Subscribe to the params of type `ids` of the node, such as `SPA_PARAM_Format`.
+    ///
+    /// The server will then emit the `param` event of the listeners of the node each time one of these params
+    /// changes, so that it does not need to be polled.
+    /// Subscribing replaces any previous subscription, so an empty `ids` unsubscribes from all params.
+    pub fn subscribe_params(&self, ids: &[u32]) -> Result<(), Error> {
+        let res = unsafe {
+            spa_interface_call_method!(
+                self.proxy.as_ptr() as *mut pw_sys::pw_node,
+                pw_sys::pw_node_methods,
+                subscribe_params,
+                ids.as_ptr() as *mut u32,
+                ids.len().try_into().expect("Too many param ids")
+            )
+        };
+
+        spa::SpaResult::from_c(res).into_result()?;
+        Ok(())
+    }
+}
+
+/// A command that can be sent to a node using [`Node::send_command`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NodeCommand {
+    /// Suspend the node, releasing its resources such as opened devices.
+    Suspend,
+    /// Pause the node.
+    Pause,
+    /// Start the node.
+    Start,
+    /// Enable the node.
+    Enable,
+    /// Disable the node.
+    Disable,
+    /// Flush all data of the node.
+    Flush,
+    /// Drain the data of the node.
+    Drain,
+}
+
+impl NodeCommand {
+    fn as_raw(&self) -> u32 {
+        match self {
+            NodeCommand::Suspend => spa_sys::spa_node_command_SPA_NODE_COMMAND_Suspend,
+            NodeCommand::Pause => spa_sys::spa_node_command_SPA_NODE_COMMAND_Pause,
+            NodeCommand::Start => spa_sys::spa_node_command_SPA_NODE_COMMAND_Start,
+            NodeCommand::Enable => spa_sys::spa_node_command_SPA_NODE_COMMAND_Enable,
+            NodeCommand::Disable => spa_sys::spa_node_command_SPA_NODE_COMMAND_Disable,
+            NodeCommand::Flush => spa_sys::spa_node_command_SPA_NODE_COMMAND_Flush,
+            NodeCommand::Drain => spa_sys::spa_node_command_SPA_NODE_COMMAND_Drain,
         }
     }
 }
@@ -60,6 +157,8 @@ struct ListenerLocalCallbacks {
 pub struct NodeListenerLocalBuilder<'a> {
     node: &'a Node,
     cbs: ListenerLocalCallbacks,
+    #[allow(clippy::type_complexity)]
+    error: Option<Box<dyn Fn(i32, i32, &str)>>,
 }
 
 pub struct NodeInfo {
@@ -165,6 +264,9 @@ pub struct NodeListener {
     listener: Pin<Box<spa_sys::spa_hook>>,
     #[allow(dead_code)]
     data: Box<ListenerLocalCallbacks>,
+    // Listener of the proxy, routing the errors about the node to the `error` callback
+    #[allow(dead_code)]
+    proxy_listener: Option<ProxyListener>,
 }
 
 impl<'a> Listener for NodeListener {}
@@ -194,16 +296,32 @@ impl<'a> NodeListenerLocalBuilder<'a> {
         self
     }
 
+    /// Set the callback called with the sequence number, the negative `errno` and the message
+    /// of the errors reported by the server about the node, e.g. when a command sent with
+    /// [`send_command`](`Node::send_command`) was rejected.
+    ///
+    /// See [`Error::from_core_error`] to convert the error into an [`Error`].
+    #[must_use]
+    pub fn error<F>(mut self, error: F) -> Self
+    where
+        F: Fn(i32, i32, &str) + 'static,
+    {
+        self.error = Some(Box::new(error));
+        self
+    }
+
     #[must_use]
     pub fn register(self) -> NodeListener {
         unsafe extern "C" fn node_events_info(
             data: *mut c_void,
             info: *const pw_sys::pw_node_info,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let info = ptr::NonNull::new(info as *mut _).expect("info is NULL");
-            let info = NodeInfo::new(info);
-            callbacks.info.as_ref().unwrap()(&info);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let info = ptr::NonNull::new(info as *mut _).expect("info is NULL");
+                let info = NodeInfo::new(info);
+                callbacks.info.as_ref().unwrap()(&info);
+            })
         }
 
         unsafe extern "C" fn node_events_param(
@@ -214,8 +332,10 @@ impl<'a> NodeListenerLocalBuilder<'a> {
             next: u32,
             _param: *const spa_sys::spa_pod,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.param.as_ref().unwrap()(seq, id, index, next);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.param.as_ref().unwrap()(seq, id, index, next);
+            })
         }
 
         let e = unsafe {
@@ -250,10 +370,15 @@ impl<'a> NodeListenerLocalBuilder<'a> {
             (listener, Box::from_raw(data))
         };
 
+        let proxy_listener = self
+            .error
+            .map(|error| self.node.proxy.add_listener_local().error(error).register());
+
         NodeListener {
             events: e,
             listener,
             data,
+            proxy_listener,
         }
     }
 }
@@ -14,10 +14,13 @@ use crate::{
 use spa::dict::ForeignDict;
 
 #[derive(Debug)]
+#[repr(transparent)]
 pub struct Port {
     proxy: Proxy,
 }
 
+impl crate::proxy::sealed::Sealed for Port {}
+
 impl ProxyT for Port {
     fn type_() -> ObjectType {
         ObjectType::Port
@@ -37,6 +40,14 @@ impl ProxyT for Port {
     {
         Self { proxy }
     }
+
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized,
+    {
+        // Safety: `Self` is a `#[repr(transparent)]` wrapper around a `Proxy`.
+        &*(proxy as *const Proxy as *const Self)
+    }
 }
 
 impl Port {
@@ -156,10 +167,12 @@ impl<'a> PortListenerLocalBuilder<'a> {
             data: *mut c_void,
             info: *const pw_sys::pw_port_info,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let info = ptr::NonNull::new(info as *mut _).expect("info is NULL");
-            let info = PortInfo::new(info);
-            callbacks.info.as_ref().unwrap()(&info);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let info = ptr::NonNull::new(info as *mut _).expect("info is NULL");
+                let info = PortInfo::new(info);
+                callbacks.info.as_ref().unwrap()(&info);
+            })
         }
 
         unsafe extern "C" fn port_events_param(
@@ -170,8 +183,10 @@ impl<'a> PortListenerLocalBuilder<'a> {
             next: u32,
             _param: *const spa_sys::spa_pod,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.param.as_ref().unwrap()(seq, id, index, next);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.param.as_ref().unwrap()(seq, id, index, next);
+            })
         }
 
         let e = unsafe {
@@ -1,5 +1,5 @@
 use spa::prelude::*;
-use std::{ffi::CString, fmt, marker::PhantomData, mem::ManuallyDrop, ptr};
+use std::{ffi::CString, fmt, iter::FromIterator, marker::PhantomData, mem::ManuallyDrop, ptr};
 
 /// A collection of key/value pairs.
 ///
@@ -66,6 +66,16 @@ macro_rules! properties {
 }
 
 impl Properties {
+    /// Create a new, empty `Properties` struct.
+    ///
+    /// To create a `Properties` struct with predefined key-value pairs, use the [`properties!`] macro instead.
+    pub fn new() -> Self {
+        unsafe {
+            let raw = pw_sys::pw_properties_new(ptr::null());
+            Self::from_ptr(ptr::NonNull::new(raw).expect("pw_properties_new() returned NULL"))
+        }
+    }
+
     /// Create a `Properties` struct from an existing raw `pw_properties` pointer.
     ///
     /// # Safety
@@ -110,6 +120,46 @@ impl Properties {
             Self::from_ptr(ptr::NonNull::new(copy).expect("pw_properties_new_dict() returned NULL"))
         }
     }
+
+    /// Create a new `Properties` from the entries of a given dictionary matching `predicate`.
+    ///
+    /// `predicate` is called with the key and value of each entry of `dict`,
+    /// and only the entries for which it returns `true` are copied.
+    ///
+    /// # Examples
+    /// Only keep the `media.*` properties:
+    /// ```rust
+    /// use pipewire::prelude::*;
+    /// use pipewire::{properties, Properties};
+    ///
+    /// let props = properties! {
+    ///     "media.class" => "Audio/Sink",
+    ///     "node.name" => "sink",
+    /// };
+    ///
+    /// let media = Properties::from_dict_filtered(&props, |key, _value| key.starts_with("media."));
+    /// assert_eq!(media.get("media.class"), Some("Audio/Sink"));
+    /// assert_eq!(media.get("node.name"), None);
+    /// ```
+    pub fn from_dict_filtered<D, F>(dict: &D, predicate: F) -> Self
+    where
+        D: ReadableDict,
+        F: Fn(&str, &str) -> bool,
+    {
+        dict.iter()
+            .filter(|(key, value)| predicate(key, value))
+            .collect()
+    }
+}
+
+impl<T: Into<Vec<u8>>> FromIterator<(T, T)> for Properties {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut props = Self::new();
+        for (key, value) in iter {
+            props.insert(key, value);
+        }
+        props
+    }
 }
 
 impl ReadableDict for Properties {
@@ -135,6 +185,12 @@ impl WritableDict for Properties {
     }
 }
 
+impl Default for Properties {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Clone for Properties {
     fn clone(&self) -> Self {
         unsafe {
@@ -158,6 +214,26 @@ impl fmt::Debug for Properties {
     }
 }
 
+/// Whether both dicts contain the same key/value pairs, regardless of their order.
+fn dict_eq<A: ReadableDict, B: ReadableDict>(a: &A, b: &B) -> bool {
+    a.len() == b.len() && a.iter().all(|(key, value)| b.get(key) == Some(value))
+}
+
+/// Properties are equal if they contain the same key/value pairs, regardless of their order.
+impl PartialEq for Properties {
+    fn eq(&self, other: &Self) -> bool {
+        dict_eq(self, other)
+    }
+}
+
+impl Eq for Properties {}
+
+impl<'a> PartialEq<PropertiesRef<'a>> for Properties {
+    fn eq(&self, other: &PropertiesRef<'a>) -> bool {
+        dict_eq(self, other)
+    }
+}
+
 pub struct PropertiesRef<'a> {
     ptr: ptr::NonNull<pw_sys::pw_properties>,
     // ensure that PropertiesRef does not outlive the object creating it
@@ -210,6 +286,21 @@ impl<'a> fmt::Debug for PropertiesRef<'a> {
     }
 }
 
+/// Properties are equal if they contain the same key/value pairs, regardless of their order.
+impl<'a, 'b> PartialEq<PropertiesRef<'b>> for PropertiesRef<'a> {
+    fn eq(&self, other: &PropertiesRef<'b>) -> bool {
+        dict_eq(self, other)
+    }
+}
+
+impl<'a> Eq for PropertiesRef<'a> {}
+
+impl<'a> PartialEq<Properties> for PropertiesRef<'a> {
+    fn eq(&self, other: &Properties) -> bool {
+        dict_eq(self, other)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +316,17 @@ mod tests {
         assert_eq!(None, iter.next());
     }
 
+    #[test]
+    fn new_empty() {
+        let mut props = Properties::new();
+        assert!(props.is_empty());
+
+        props.insert("K0", "V0");
+        assert_eq!(props.get("K0"), Some("V0"));
+
+        assert!(Properties::default().is_empty());
+    }
+
     #[test]
     fn remove() {
         let mut props = properties! {
@@ -247,6 +349,55 @@ mod tests {
         assert_eq!(Some("V1"), props.get("K1"));
     }
 
+    #[test]
+    fn set_default() {
+        let mut props = properties! {
+            "K0" => "V0"
+        };
+
+        props.set_default("K0", "default");
+        props.set_default("K1", "default");
+        assert_eq!(Some("V0"), props.get("K0"));
+        assert_eq!(Some("default"), props.get("K1"));
+    }
+
+    #[test]
+    fn from_dict_filtered() {
+        let props = properties! {
+            "media.class" => "Audio/Sink",
+            "media.role" => "Music",
+            "node.name" => "sink"
+        };
+
+        let filtered = Properties::from_dict_filtered(&props, |key, _| key.starts_with("media."));
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered.get("media.class"), Some("Audio/Sink"));
+        assert_eq!(filtered.get("media.role"), Some("Music"));
+        assert_eq!(filtered.get("node.name"), None);
+
+        let filtered = Properties::from_dict_filtered(&props, |_, value| value == "sink");
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered.get("node.name"), Some("sink"));
+    }
+
+    #[test]
+    fn from_iter() {
+        let props: Properties = vec![("K0", "V0"), ("K1", "V1")].into_iter().collect();
+
+        assert_eq!(props.get("K0"), Some("V0"));
+        assert_eq!(props.get("K1"), Some("V1"));
+    }
+
+    #[test]
+    fn insert_fmt() {
+        let mut props = Properties::new();
+
+        props.insert_fmt("K0", 48000);
+        props.insert_fmt(String::from("K1"), 1.5);
+        assert_eq!(Some("48000"), props.get("K0"));
+        assert_eq!(Some("1.5"), props.get("K1"));
+    }
+
     #[test]
     fn clone() {
         let props1 = properties! {
@@ -280,6 +431,32 @@ mod tests {
         assert_eq!(props.get("K1"), Some("V1"));
     }
 
+    #[test]
+    fn eq() {
+        let props1 = properties! {
+            "K0" => "V0",
+            "K1" => "V1"
+        };
+        let mut props2 = properties! {
+            "K1" => "V1",
+            "K0" => "V0"
+        };
+        assert_eq!(props1, props2);
+
+        let props_ref =
+            unsafe { PropertiesRef::from_ptr(std::ptr::NonNull::new(props1.as_ptr()).unwrap()) };
+        assert_eq!(props_ref, props2);
+        assert_eq!(props2, props_ref);
+
+        props2.insert("K1", "Other");
+        assert_ne!(props1, props2);
+
+        props2.insert("K1", "V1");
+        props2.insert("K2", "V2");
+        assert_ne!(props1, props2);
+        assert_ne!(props2, props1);
+    }
+
     #[test]
     fn properties_ref() {
         let props = properties! {
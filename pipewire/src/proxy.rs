@@ -48,13 +48,32 @@ impl Proxy {
         }
     }
 
+    /// Returns `true` if the proxy represents an object of the type of `P`.
+    ///
+    /// This can be used to check the type of the proxy without giving up its ownership,
+    /// unlike downcasting it.
+    pub fn is<P: ProxyT>(&self) -> bool {
+        P::type_() == self.get_type().0
+    }
+
+    /// Get a reference to the proxy as a `P`, if it represents an object of the type of `P`.
+    ///
+    /// Returns `None` if the type does not match, see [`is`](`Self::is`).
+    pub fn downcast_ref<P: ProxyT>(&self) -> Option<&P> {
+        if self.is::<P>() {
+            Some(unsafe { P::from_proxy_ref_unchecked(self) })
+        } else {
+            None
+        }
+    }
+
     /// Attempt to downcast the proxy to the provided type.
     ///
     /// The downcast will fail if the type that the proxy represents does not match the provided type. \
     /// In that case, the function returns `(self, Error::WrongProxyType)` so that the proxy is not lost.
     pub(crate) fn downcast<P: ProxyT>(self) -> Result<P, (Self, Error)> {
         // Make sure the proxy we got has the type that is requested
-        if P::type_() == self.get_type().0 {
+        if self.is::<P>() {
             unsafe { Ok(P::from_proxy_unchecked(self)) }
         } else {
             Err((self, Error::WrongProxyType))
@@ -82,8 +101,16 @@ impl fmt::Debug for Proxy {
     }
 }
 
+pub(crate) mod sealed {
+    /// Supertrait of [`ProxyT`](`super::ProxyT`) preventing it from being implemented outside of this crate.
+    pub trait Sealed {}
+}
+
 // Trait implemented by high level proxy wrappers
-pub trait ProxyT {
+//
+// The trait is sealed, as `Proxy::downcast_ref` relies on its implementors being
+// `#[repr(transparent)]` wrappers around a `Proxy`.
+pub trait ProxyT: sealed::Sealed {
     // Add Sized restriction on those methods so it can be used as a
     // trait object, see E0038
     fn type_() -> ObjectType
@@ -104,6 +131,17 @@ pub trait ProxyT {
     unsafe fn from_proxy_unchecked(proxy: Proxy) -> Self
     where
         Self: Sized;
+
+    /// Downcast a reference to the provided proxy to a reference to `Self` without checking that the type matches.
+    ///
+    /// This is used by [`Proxy::downcast_ref`], which checks the type first.
+    ///
+    /// # Safety
+    /// It must be manually ensured that the provided proxy is actually a proxy representing the created type. \
+    /// Otherwise, undefined behaviour may occur.
+    unsafe fn from_proxy_ref_unchecked(proxy: &Proxy) -> &Self
+    where
+        Self: Sized;
 }
 
 // Trait implemented by listener on high level proxy wrappers.
@@ -177,6 +215,11 @@ impl<'a> ProxyListenerLocalBuilder<'a> {
         self
     }
 
+    /// Set the callback called with the sequence number, the negative `errno` and the message
+    /// of the errors reported by the server about the object of the proxy.
+    ///
+    /// The server reports these errors through the `error` event of the core,
+    /// which is routed to the proxy whose id it is about.
     #[must_use]
     pub fn error<F>(mut self, error: F) -> Self
     where
@@ -189,23 +232,31 @@ impl<'a> ProxyListenerLocalBuilder<'a> {
     #[must_use]
     pub fn register(self) -> ProxyListener {
         unsafe extern "C" fn proxy_destroy(data: *mut c_void) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.destroy.as_ref().unwrap()();
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.destroy.as_ref().unwrap()();
+            })
         }
 
         unsafe extern "C" fn proxy_bound(data: *mut c_void, global_id: u32) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.bound.as_ref().unwrap()(global_id);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.bound.as_ref().unwrap()(global_id);
+            })
         }
 
         unsafe extern "C" fn proxy_removed(data: *mut c_void) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.removed.as_ref().unwrap()();
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.removed.as_ref().unwrap()();
+            })
         }
 
         unsafe extern "C" fn proxy_done(data: *mut c_void, seq: i32) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.done.as_ref().unwrap()(seq);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.done.as_ref().unwrap()(seq);
+            })
         }
 
         unsafe extern "C" fn proxy_error(
@@ -214,9 +265,11 @@ impl<'a> ProxyListenerLocalBuilder<'a> {
             res: i32,
             message: *const c_char,
         ) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            let message = CStr::from_ptr(message).to_str().unwrap();
-            callbacks.error.as_ref().unwrap()(seq, res, message);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                let message = CStr::from_ptr(message).to_str().unwrap();
+                callbacks.error.as_ref().unwrap()(seq, res, message);
+            })
         }
 
         let e = unsafe {
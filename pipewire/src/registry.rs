@@ -6,14 +6,16 @@ use libc::{c_char, c_void};
 use std::mem;
 use std::pin::Pin;
 use std::{
+    cell::RefCell,
     ffi::{CStr, CString},
     ptr,
+    rc::Rc,
 };
 
 use crate::{
     proxy::{Proxy, ProxyT},
     types::ObjectType,
-    Error, Properties,
+    Core, Error, MainLoop, Properties,
 };
 use spa::{dict::ForeignDict, prelude::*};
 
@@ -63,6 +65,50 @@ impl Registry {
         Proxy::new(proxy).downcast().map_err(|(_, e)| e)
     }
 
+    /// Collect the globals announced by the server.
+    ///
+    /// This registers a temporary listener and does a roundtrip to the server by running `main_loop`
+    /// until all pending events have been processed, then returns the globals received in the meantime.
+    ///
+    /// The server only announces the existing globals once, right after the registry has been created.
+    /// This should thus be called on a freshly created registry, before `main_loop` has been run,
+    /// otherwise only globals added in the meantime will be returned.
+    ///
+    /// # Errors
+    /// Returns [`Error::ServerError`] if the server reports an error about the core, such as the connection
+    /// being lost, see [`Core::roundtrip`](`crate::CoreInner::roundtrip`).
+    ///
+    /// # Panics
+    /// If `main_loop` is already running, e.g. if this is called from a callback of the loop,
+    /// see [`MainLoop::run`](`crate::MainLoopInner::run`).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// let mainloop = pipewire::MainLoop::new()?;
+    /// let context = pipewire::Context::new(&mainloop)?;
+    /// let core = context.connect(None)?;
+    /// let registry = core.get_registry()?;
+    ///
+    /// for global in registry.list(&core, &mainloop)? {
+    ///     println!("{}: {}", global.id, global.type_);
+    /// }
+    /// # Ok::<(), pipewire::Error>(())
+    /// ```
+    pub fn list(&self, core: &Core, main_loop: &MainLoop) -> Result<Vec<OwnedGlobalObject>, Error> {
+        let globals = Rc::new(RefCell::new(Vec::new()));
+        let _listener = self
+            .add_listener_local()
+            .global({
+                let globals = globals.clone();
+                move |global| globals.borrow_mut().push(global.to_owned())
+            })
+            .register();
+
+        core.roundtrip(main_loop)?;
+
+        Ok(globals.take())
+    }
+
     /// Attempt to destroy the global object with the specified id on the remote.
     pub fn destroy_global(&self, global_id: u32) -> spa::SpaResult {
         let result = unsafe {
@@ -89,6 +135,7 @@ impl Drop for Registry {
 #[derive(Default)]
 struct ListenerLocalCallbacks {
     global: Option<Box<dyn Fn(&GlobalObject<ForeignDict>)>>,
+    global_type: Option<ObjectType>,
     global_remove: Option<Box<dyn Fn(u32)>>,
 }
 
@@ -119,6 +166,21 @@ impl<'a> ListenerLocalBuilder<'a> {
         F: Fn(&GlobalObject<ForeignDict>) + 'static,
     {
         self.cbs.global = Some(Box::new(global));
+        self.cbs.global_type = None;
+        self
+    }
+
+    /// Set the `global` callback, only invoking it for globals of type `type_`.
+    ///
+    /// Globals of other types are skipped before their properties are looked up.
+    /// This replaces any callback previously set using [`global`](`Self::global`).
+    #[must_use]
+    pub fn global_filtered<F>(mut self, type_: ObjectType, global: F) -> Self
+    where
+        F: Fn(&GlobalObject<ForeignDict>) + 'static,
+    {
+        self.cbs.global = Some(Box::new(global));
+        self.cbs.global_type = Some(type_);
         self
     }
 
@@ -141,15 +203,22 @@ impl<'a> ListenerLocalBuilder<'a> {
             version: u32,
             props: *const spa_sys::spa_dict,
         ) {
-            let type_ = CStr::from_ptr(type_).to_str().unwrap();
-            let obj = GlobalObject::new(id, permissions, type_, version, props);
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.global.as_ref().unwrap()(&obj);
+            crate::utils::catch_panic(|| {
+                let type_ = ObjectType::from_str(CStr::from_ptr(type_).to_str().unwrap());
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                if matches!(callbacks.global_type, Some(ref wanted) if *wanted != type_) {
+                    return;
+                }
+                let obj = GlobalObject::new(id, permissions, type_, version, props);
+                callbacks.global.as_ref().unwrap()(&obj);
+            })
         }
 
         unsafe extern "C" fn registry_events_global_remove(data: *mut c_void, id: u32) {
-            let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
-            callbacks.global_remove.as_ref().unwrap()(id);
+            crate::utils::catch_panic(|| {
+                let callbacks = (data as *mut ListenerLocalCallbacks).as_ref().unwrap();
+                callbacks.global_remove.as_ref().unwrap()(id);
+            })
         }
 
         let e = unsafe {
@@ -201,12 +270,20 @@ bitflags! {
     }
 }
 
+/// A global object announced by the server through the registry.
+///
+/// Its [`Debug`] output includes the entries of `props`.
 #[derive(Debug)]
 pub struct GlobalObject<D: ReadableDict> {
+    /// The id of the global.
     pub id: u32,
+    /// The permissions the client has on the global.
     pub permissions: Permission,
+    /// The interface type of the global.
     pub type_: ObjectType,
+    /// The version of the interface of the global.
     pub version: u32,
+    /// The properties of the global, if any.
     pub props: Option<D>,
 }
 
@@ -214,11 +291,10 @@ impl GlobalObject<ForeignDict> {
     fn new(
         id: u32,
         permissions: u32,
-        type_: &str,
+        type_: ObjectType,
         version: u32,
         props: *const spa_sys::spa_dict,
     ) -> Self {
-        let type_ = ObjectType::from_str(type_);
         let permissions = Permission::from_bits(permissions).expect("invalid permissions");
         let props = props as *mut _;
         let props = ptr::NonNull::new(props).map(|ptr| unsafe { ForeignDict::from_ptr(ptr) });
@@ -233,8 +309,15 @@ impl GlobalObject<ForeignDict> {
     }
 }
 
+/// A [`GlobalObject`] owning a copy of its properties.
+///
+/// Unlike the global passed to the `global` callback of the registry, whose properties are only valid during the
+/// callback, it can be stored for later use, e.g. to maintain a model of the objects of the server.
+pub type OwnedGlobalObject = GlobalObject<Properties>;
+
 impl<D: ReadableDict> GlobalObject<D> {
-    pub fn to_owned(&self) -> GlobalObject<Properties> {
+    /// Copy the global and its properties into an [`OwnedGlobalObject`].
+    pub fn to_owned(&self) -> OwnedGlobalObject {
         GlobalObject {
             id: self.id,
             permissions: self.permissions,
@@ -248,6 +331,12 @@ impl<D: ReadableDict> GlobalObject<D> {
     }
 }
 
+impl Clone for OwnedGlobalObject {
+    fn clone(&self) -> Self {
+        self.to_owned()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,10 +354,78 @@ mod tests {
         assert_eq!(o.to_str(), "PipeWire:Interface:Badger");
     }
 
+    #[test]
+    fn object_type_standard_interfaces() {
+        let types = [
+            (ObjectType::Client, pw_sys::PW_VERSION_CLIENT),
+            (ObjectType::ClientNode, pw_sys::PW_VERSION_CLIENT_NODE),
+            (ObjectType::Device, pw_sys::PW_VERSION_DEVICE),
+            (ObjectType::Factory, pw_sys::PW_VERSION_FACTORY),
+            (ObjectType::Link, pw_sys::PW_VERSION_LINK),
+            (ObjectType::Metadata, pw_sys::PW_VERSION_METADATA),
+            (ObjectType::Module, pw_sys::PW_VERSION_MODULE),
+            (ObjectType::Node, pw_sys::PW_VERSION_NODE),
+            (ObjectType::Port, pw_sys::PW_VERSION_PORT),
+            (ObjectType::Profiler, pw_sys::PW_VERSION_PROFILER),
+        ];
+
+        for (type_, version) in types.iter() {
+            assert!(type_.to_str().starts_with("PipeWire:Interface:"));
+            assert_eq!(&ObjectType::from_str(type_.to_str()), type_);
+            assert_eq!(type_.client_version(), *version);
+        }
+        assert_eq!(ObjectType::Device.to_str(), "PipeWire:Interface:Device");
+    }
+
     #[test]
     #[should_panic(expected = "Invalid object type")]
     fn client_version_panic() {
         let o = ObjectType::Other("PipeWire:Interface:Badger".to_string());
         assert_eq!(o.client_version(), 0);
     }
+
+    #[test]
+    fn global_object_debug() {
+        let global = GlobalObject {
+            id: 42,
+            permissions: Permission::R,
+            type_: ObjectType::Node,
+            version: 3,
+            props: Some(crate::properties! {
+                "node.name" => "test-node"
+            }),
+        };
+
+        let debug = format!("{:?}", global);
+        assert!(debug.contains("id: 42"));
+        assert!(debug.contains("type_: Node"));
+        assert!(debug.contains(r#""node.name": "test-node""#));
+    }
+
+    #[test]
+    fn owned_global_object_clone() {
+        let global: OwnedGlobalObject = GlobalObject {
+            id: 42,
+            permissions: Permission::R | Permission::X,
+            type_: ObjectType::Node,
+            version: 3,
+            props: Some(crate::properties! {
+                "node.name" => "test-node"
+            }),
+        };
+
+        let clone = global.clone();
+        drop(global);
+        assert_eq!(clone.id, 42);
+        assert_eq!(clone.permissions, Permission::R | Permission::X);
+        assert_eq!(clone.type_, ObjectType::Node);
+        assert_eq!(clone.version, 3);
+        assert_eq!(
+            clone
+                .props
+                .as_ref()
+                .and_then(|props| props.get("node.name")),
+            Some("test-node")
+        );
+    }
 }
@@ -6,6 +6,7 @@
 use crate::buffer::Buffer;
 use crate::{error::Error, Core, Loop, MainLoop, Properties, PropertiesRef};
 use bitflags::bitflags;
+use spa::pod::{deserialize::PodDeserializer, Object, Value};
 use spa::result::SpaResult;
 use std::fmt::Debug;
 use std::{
@@ -15,6 +16,16 @@ use std::{
     ptr,
 };
 
+mod io;
+use io::IoAreas;
+pub use io::{IoPosition, IoRateMatch};
+mod negotiator;
+pub use negotiator::Negotiator;
+mod param_arena;
+pub use param_arena::ParamArena;
+mod ring_buffer;
+pub use ring_buffer::{RingBuffer, RingBufferReader, RingBufferWriter};
+
 #[derive(Debug)]
 pub enum StreamState {
     Error(String),
@@ -42,6 +53,29 @@ impl StreamState {
             }
         }
     }
+
+    /// Returns `true` if the stream is in the error state.
+    pub fn is_error(&self) -> bool {
+        matches!(self, StreamState::Error(_))
+    }
+
+    /// Get the error message if the stream is in the error state, or `None` otherwise.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pipewire::stream::StreamState;
+    ///
+    /// let state = StreamState::Error("no target node available".to_string());
+    /// assert!(state.is_error());
+    /// assert_eq!(state.error_message(), Some("no target node available"));
+    /// assert_eq!(StreamState::Streaming.error_message(), None);
+    /// ```
+    pub fn error_message(&self) -> Option<&str> {
+        match self {
+            StreamState::Error(error) => Some(error),
+            _ => None,
+        }
+    }
 }
 
 /// A wrapper around the pipewire stream interface. Streams are a higher
@@ -64,8 +98,10 @@ enum KeepAlive<D> {
         _events: Pin<Box<pw_sys::pw_stream_events>>,
         _data: Box<ListenerLocalCallbacks<D>>,
     },
-    // Temporary stream for callbacks
-    Temp,
+    // Temporary stream for callbacks, pointing to the IO areas tracked by the listener
+    Temp {
+        io: *const IoAreas,
+    },
 }
 
 impl<D> Stream<D> {
@@ -161,7 +197,7 @@ impl<D> Stream<D> {
         flags: StreamFlags,
         params: &mut [*const spa_sys::spa_pod],
     ) -> Result<(), Error> {
-        let r = unsafe {
+        let r = crate::utils::resuming_panic(|| unsafe {
             pw_sys::pw_stream_connect(
                 self.as_ptr(),
                 direction.as_raw(),
@@ -170,7 +206,7 @@ impl<D> Stream<D> {
                 params.as_mut_ptr(),
                 params.len() as u32,
             )
-        };
+        });
 
         SpaResult::from_c(r).into_sync_result()?;
         Ok(())
@@ -182,9 +218,9 @@ impl<D> Stream<D> {
     /// parameters for the stream.
     // FIXME: high-level API for params
     pub fn update_params(&self, params: &mut [*const spa_sys::spa_pod]) -> Result<(), Error> {
-        let r = unsafe {
+        let r = crate::utils::resuming_panic(|| unsafe {
             pw_sys::pw_stream_update_params(self.as_ptr(), params.as_mut_ptr(), params.len() as u32)
-        };
+        });
 
         SpaResult::from_c(r).into_sync_result()?;
         Ok(())
@@ -192,7 +228,9 @@ impl<D> Stream<D> {
 
     /// Activate or deactivate the stream
     pub fn set_active(&self, active: bool) -> Result<(), Error> {
-        let r = unsafe { pw_sys::pw_stream_set_active(self.as_ptr(), active) };
+        let r = crate::utils::resuming_panic(|| unsafe {
+            pw_sys::pw_stream_set_active(self.as_ptr(), active)
+        });
 
         SpaResult::from_c(r).into_sync_result()?;
         Ok(())
@@ -212,6 +250,12 @@ impl<D> Stream<D> {
         pw_sys::pw_stream_dequeue_buffer(self.as_ptr())
     }
 
+    /// Take a [`Buffer`] from the Stream
+    ///
+    /// The buffer is given back to the stream when it is dropped, including when unwinding
+    /// from a panic in the `process` callback.
+    ///
+    /// Returns `None` if no buffer is available.
     pub fn dequeue_buffer(&self) -> Option<Buffer<D>> {
         unsafe { Buffer::from_raw(self.dequeue_raw_buffer(), self) }
     }
@@ -236,7 +280,8 @@ impl<D> Stream<D> {
 
     /// Disconnect the stream
     pub fn disconnect(&self) -> Result<(), Error> {
-        let r = unsafe { pw_sys::pw_stream_disconnect(self.as_ptr()) };
+        let r =
+            crate::utils::resuming_panic(|| unsafe { pw_sys::pw_stream_disconnect(self.as_ptr()) });
 
         SpaResult::from_c(r).into_sync_result()?;
         Ok(())
@@ -244,20 +289,38 @@ impl<D> Stream<D> {
 
     /// Set the stream in error state
     ///
+    /// `res` is a negative `errno`, such as `-libc::EIO`.
+    ///
+    /// This can be called from the `process` callback, e.g. when processing the data failed.
+    ///
     /// # Panics
     /// Will panic if `error` contains a 0 byte.
     ///
-    pub fn set_error(&mut self, res: i32, error: &str) {
+    pub fn set_error(&self, res: i32, error: &str) {
         let error = CString::new(error).expect("failed to convert error to CString");
-        unsafe {
+        crate::utils::resuming_panic(|| unsafe {
             pw_sys::pw_stream_set_error(self.as_ptr(), res, error.as_c_str().as_ptr());
-        }
+        });
+    }
+
+    /// Set the stream in error state, using the `errno` of `error`.
+    ///
+    /// This is a variant of [`set_error`](`Self::set_error`) for propagating an error returned
+    /// by a SPA method, so that the session manager can react to the actual cause.
+    ///
+    /// # Panics
+    /// Will panic if `message` contains a 0 byte.
+    pub fn set_spa_error(&self, error: &spa::Error, message: &str) {
+        let errno::Errno(errno) = error.errno();
+        self.set_error(-errno, message);
     }
 
     /// Flush the stream. When  `drain` is `true`, the `drained` callback will
     /// be called when all data is played or recorded.
     pub fn flush(&self, drain: bool) -> Result<(), Error> {
-        let r = unsafe { pw_sys::pw_stream_flush(self.as_ptr(), drain) };
+        let r = crate::utils::resuming_panic(|| unsafe {
+            pw_sys::pw_stream_flush(self.as_ptr(), drain)
+        });
 
         SpaResult::from_c(r).into_sync_result()?;
         Ok(())
@@ -266,6 +329,8 @@ impl<D> Stream<D> {
     // TODO: pw_stream_set_control()
 
     // getters
+    //
+    // The `process` callback is only given a `&Stream<D>`, so all getters must take `&self`.
 
     /// Get the name of the stream.
     pub fn name(&self) -> String {
@@ -299,6 +364,45 @@ impl<D> Stream<D> {
         unsafe { pw_sys::pw_stream_get_node_id(self.as_ptr()) }
     }
 
+    /// Get the current time of the stream's clock in nanoseconds.
+    ///
+    /// This value can be compared with the `now` field of the stream's time,
+    /// and is suitable to timestamp data in the `process` callback:
+    /// it does not allocate or lock, so it is safe to call from a realtime thread.
+    #[cfg(feature = "v1_1")]
+    pub fn nsec(&self) -> u64 {
+        unsafe { pw_sys::pw_stream_get_nsec(self.as_ptr()) }
+    }
+
+    /// Get the `SPA_IO_Position` area of the stream, describing the clock and position of the graph.
+    ///
+    /// The IO areas are tracked by the listener of the stream, so they are only available
+    /// on streams created using [`Stream::simple`] and on the stream passed to the `process` callback.
+    /// Returns `None` otherwise, or if the server did not provide the area.
+    ///
+    /// This does not allocate or lock, so it is safe to call from a realtime thread.
+    pub fn io_position(&self) -> Option<&IoPosition> {
+        // Safety: The areas are cleared by the listener when the server removes them.
+        self.io_areas().and_then(|io| unsafe { io.position() })
+    }
+
+    /// Get the `SPA_IO_RateMatch` area of the stream, used to adjust the rate of the resampler of the stream.
+    ///
+    /// The area is only provided by the server if the stream is resampled.
+    /// See [`io_position`](`Self::io_position`) for the streams where the IO areas are available.
+    pub fn io_rate_match(&self) -> Option<&IoRateMatch> {
+        // Safety: The areas are cleared by the listener when the server removes them.
+        self.io_areas().and_then(|io| unsafe { io.rate_match() })
+    }
+
+    fn io_areas(&self) -> Option<&IoAreas> {
+        match &self._alive {
+            KeepAlive::Normal { .. } => None,
+            KeepAlive::Simple { _data, .. } => Some(&_data.io),
+            KeepAlive::Temp { io } => unsafe { io.as_ref() },
+        }
+    }
+
     // TODO: pw_stream_get_core()
     // TODO: pw_stream_get_time()
 }
@@ -369,6 +473,7 @@ impl<D> std::fmt::Debug for Stream<D> {
 }
 
 type ParamChangedCB<D> = dyn Fn(u32, &mut D, *const spa_sys::spa_pod);
+type FormatChangedCB<D> = dyn Fn(&mut D, Option<Object>);
 type ProcessCB<D> = dyn Fn(&Stream<D>, &mut D);
 
 pub struct ListenerLocalCallbacks<D> {
@@ -377,11 +482,18 @@ pub struct ListenerLocalCallbacks<D> {
     #[allow(clippy::type_complexity)]
     pub io_changed: Option<Box<dyn Fn(u32, *mut os::raw::c_void, u32)>>,
     pub param_changed: Option<Box<ParamChangedCB<D>>>,
+    pub format_changed: Option<Box<FormatChangedCB<D>>>,
     pub add_buffer: Option<Box<dyn Fn(*mut pw_sys::pw_buffer)>>,
     pub remove_buffer: Option<Box<dyn Fn(*mut pw_sys::pw_buffer)>>,
     pub process: Option<Box<ProcessCB<D>>>,
     pub drained: Option<Box<dyn Fn()>>,
+    #[cfg(feature = "v0_3_49")]
+    pub command: Option<Box<dyn Fn(*const spa_sys::spa_command)>>,
+    #[cfg(feature = "v0_3_49")]
+    pub trigger_done: Option<Box<dyn Fn()>>,
     pub user_data: D,
+    negotiator: Option<Negotiator>,
+    io: IoAreas,
     stream: Option<ptr::NonNull<pw_sys::pw_stream>>,
 }
 
@@ -391,12 +503,19 @@ impl<D> ListenerLocalCallbacks<D> {
             process: Default::default(),
             stream: Default::default(),
             drained: Default::default(),
+            #[cfg(feature = "v0_3_49")]
+            command: Default::default(),
+            #[cfg(feature = "v0_3_49")]
+            trigger_done: Default::default(),
             add_buffer: Default::default(),
             control_info: Default::default(),
             io_changed: Default::default(),
             param_changed: Default::default(),
+            format_changed: Default::default(),
             remove_buffer: Default::default(),
             state_changed: Default::default(),
+            negotiator: Default::default(),
+            io: Default::default(),
             user_data,
         }
     }
@@ -415,13 +534,15 @@ impl<D> ListenerLocalCallbacks<D> {
             new: pw_sys::pw_stream_state,
             error: *const os::raw::c_char,
         ) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
-                if let Some(ref cb) = state.state_changed {
-                    let old = StreamState::from_raw(old, error);
-                    let new = StreamState::from_raw(new, error);
-                    cb(old, new)
-                };
-            }
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.state_changed {
+                        let old = StreamState::from_raw(old, error);
+                        let new = StreamState::from_raw(new, error);
+                        cb(old, new)
+                    };
+                }
+            })
         }
 
         unsafe extern "C" fn on_control_info<D>(
@@ -429,11 +550,13 @@ impl<D> ListenerLocalCallbacks<D> {
             id: u32,
             control: *const pw_sys::pw_stream_control,
         ) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
-                if let Some(ref cb) = state.control_info {
-                    cb(id, control);
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.control_info {
+                        cb(id, control);
+                    }
                 }
-            }
+            })
         }
 
         unsafe extern "C" fn on_io_changed<D>(
@@ -442,11 +565,14 @@ impl<D> ListenerLocalCallbacks<D> {
             area: *mut os::raw::c_void,
             size: u32,
         ) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
-                if let Some(ref cb) = state.io_changed {
-                    cb(id, area, size);
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    state.io.update(id, area, size);
+                    if let Some(ref cb) = state.io_changed {
+                        cb(id, area, size);
+                    }
                 }
-            }
+            })
         }
 
         unsafe extern "C" fn on_param_changed<D>(
@@ -454,56 +580,137 @@ impl<D> ListenerLocalCallbacks<D> {
             id: u32,
             param: *const spa_sys::spa_pod,
         ) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
-                if let Some(ref cb) = state.param_changed {
-                    cb(id, &mut state.user_data, param);
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
+                    if let (Some(negotiator), Some(ptr)) = (&state.negotiator, state.stream) {
+                        let stream = Stream {
+                            ptr,
+                            _alive: KeepAlive::Temp { io: &state.io },
+                        };
+                        if negotiator.handle_param_changed(&stream, id, param).is_err() {
+                            let error = CString::new("Failed to update stream params").unwrap();
+                            pw_sys::pw_stream_set_error(ptr.as_ptr(), -libc::EIO, error.as_ptr());
+                        }
+                    }
+                    if let Some(ref cb) = state.param_changed {
+                        cb(id, &mut state.user_data, param);
+                    }
+                    if let Some(ref cb) = state.format_changed {
+                        if id == spa_sys::spa_param_type_SPA_PARAM_Format {
+                            match ptr::NonNull::new(param as *mut spa_sys::spa_pod)
+                                .map(|param| PodDeserializer::deserialize_ptr::<Value>(param))
+                            {
+                                None => cb(&mut state.user_data, None),
+                                Some(Ok(Value::Object(format))) => {
+                                    cb(&mut state.user_data, Some(format))
+                                }
+                                Some(_) => {
+                                    if let Some(ptr) = state.stream {
+                                        let error =
+                                            CString::new("Failed to deserialize format").unwrap();
+                                        pw_sys::pw_stream_set_error(
+                                            ptr.as_ptr(),
+                                            -libc::EINVAL,
+                                            error.as_ptr(),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
-            }
+            })
         }
 
         unsafe extern "C" fn on_add_buffer<D>(
             data: *mut ::std::os::raw::c_void,
             buffer: *mut pw_sys::pw_buffer,
         ) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
-                if let Some(ref cb) = state.add_buffer {
-                    cb(buffer);
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.add_buffer {
+                        cb(buffer);
+                    }
                 }
-            }
+            })
         }
 
         unsafe extern "C" fn on_remove_buffer<D>(
             data: *mut ::std::os::raw::c_void,
             buffer: *mut pw_sys::pw_buffer,
         ) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
-                if let Some(ref cb) = state.remove_buffer {
-                    cb(buffer);
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.remove_buffer {
+                        cb(buffer);
+                    }
                 }
-            }
+            })
         }
 
         unsafe extern "C" fn on_process<D>(data: *mut ::std::os::raw::c_void) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
-                if let Some(ref cb) = state.process {
-                    let stream = state
-                        .stream
-                        .map(|ptr| Stream {
-                            ptr,
-                            _alive: KeepAlive::Temp,
-                        })
-                        .expect("stream cannot be null");
-                    cb(&stream, &mut state.user_data);
-                }
-            }
+            crate::utils::catch_panic_or(
+                || {
+                    if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
+                        if let Some(ref cb) = state.process {
+                            let stream = state
+                                .stream
+                                .map(|ptr| Stream {
+                                    ptr,
+                                    _alive: KeepAlive::Temp { io: &state.io },
+                                })
+                                .expect("stream cannot be null");
+                            cb(&stream, &mut state.user_data);
+                        }
+                    }
+                },
+                || {
+                    // The panic is lost, e.g. with `RT_PROCESS` on the data loop thread,
+                    // so stop calling the callback and report the failure to the application.
+                    if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_mut() {
+                        state.process = None;
+                        if let Some(ptr) = state.stream {
+                            let error = CString::new("Panic in the process callback").unwrap();
+                            pw_sys::pw_stream_set_error(ptr.as_ptr(), -libc::EIO, error.as_ptr());
+                        }
+                    }
+                },
+            )
         }
 
         unsafe extern "C" fn on_drained<D>(data: *mut ::std::os::raw::c_void) {
-            if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
-                if let Some(ref cb) = state.drained {
-                    cb();
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.drained {
+                        cb();
+                    }
                 }
-            }
+            })
+        }
+
+        #[cfg(feature = "v0_3_49")]
+        unsafe extern "C" fn on_command<D>(
+            data: *mut ::std::os::raw::c_void,
+            command: *const spa_sys::spa_command,
+        ) {
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.command {
+                        cb(command);
+                    }
+                }
+            })
+        }
+
+        #[cfg(feature = "v0_3_49")]
+        unsafe extern "C" fn on_trigger_done<D>(data: *mut ::std::os::raw::c_void) {
+            crate::utils::catch_panic(|| {
+                if let Some(state) = (data as *mut ListenerLocalCallbacks<D>).as_ref() {
+                    if let Some(ref cb) = state.trigger_done {
+                        cb();
+                    }
+                }
+            })
         }
 
         let events = unsafe {
@@ -516,10 +723,12 @@ impl<D> ListenerLocalCallbacks<D> {
             if callbacks.control_info.is_some() {
                 events.control_info = Some(on_control_info::<D>);
             }
-            if callbacks.io_changed.is_some() {
-                events.io_changed = Some(on_io_changed::<D>);
-            }
-            if callbacks.param_changed.is_some() {
+            // Always listen to `io_changed` to keep track of the IO areas.
+            events.io_changed = Some(on_io_changed::<D>);
+            if callbacks.param_changed.is_some()
+                || callbacks.format_changed.is_some()
+                || callbacks.negotiator.is_some()
+            {
                 events.param_changed = Some(on_param_changed::<D>);
             }
             if callbacks.add_buffer.is_some() {
@@ -534,6 +743,15 @@ impl<D> ListenerLocalCallbacks<D> {
             if callbacks.drained.is_some() {
                 events.drained = Some(on_drained::<D>);
             }
+            #[cfg(feature = "v0_3_49")]
+            {
+                if callbacks.command.is_some() {
+                    events.command = Some(on_command::<D>);
+                }
+                if callbacks.trigger_done.is_some() {
+                    events.trigger_done = Some(on_trigger_done::<D>);
+                }
+            }
 
             events
         };
@@ -564,6 +782,9 @@ pub trait ListenerBuilderT<D>: Sized {
     }
 
     /// Set the callback for the `io_changed` event.
+    ///
+    /// The `SPA_IO_Position` and `SPA_IO_RateMatch` areas can also be accessed using
+    /// [`Stream::io_position`] and [`Stream::io_rate_match`].
     fn io_changed<F>(mut self, callback: F) -> Self
     where
         F: Fn(u32, *mut os::raw::c_void, u32) + 'static,
@@ -581,6 +802,29 @@ pub trait ListenerBuilderT<D>: Sized {
         self
     }
 
+    /// Set the callback called when the `Format` param changes.
+    ///
+    /// This is a higher level alternative to [`param_changed`](`Self::param_changed`),
+    /// only called for the `Format` param, which is deserialized into an [`Object`].
+    /// The callback receives `None` if the format was cleared.
+    ///
+    /// If the format can not be deserialized, the callback is not called and the stream is put into the error state.
+    fn format_changed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut D, Option<Object>) + 'static,
+    {
+        self.callbacks().format_changed = Some(Box::new(callback));
+        self
+    }
+
+    /// Let `negotiator` handle format negotiation in the `param_changed` event.
+    ///
+    /// The negotiator is called before the callback set with [`param_changed`](`Self::param_changed`), if any.
+    fn negotiate(mut self, negotiator: Negotiator) -> Self {
+        self.callbacks().negotiator = Some(negotiator);
+        self
+    }
+
     /// Set the callback for the `add_buffer` event.
     fn add_buffer<F>(mut self, callback: F) -> Self
     where
@@ -600,6 +844,13 @@ pub trait ListenerBuilderT<D>: Sized {
     }
 
     /// Set the callback for the `process` event.
+    ///
+    /// If the callback panics, the [`Buffer`]s it dequeued are still given back to the stream while unwinding.
+    /// The panic is resumed from [`MainLoop::run`](`crate::MainLoopInner::run`) if the callback is called
+    /// by the main loop.
+    /// With [`RT_PROCESS`](`StreamFlags::RT_PROCESS`), the callback is called on the data loop thread
+    /// where the panic can not be resumed, so it is only logged, the stream is put in error state
+    /// and the callback is not called anymore.
     fn process<F>(mut self, callback: F) -> Self
     where
         F: Fn(&Stream<D>, &mut D) + 'static,
@@ -616,6 +867,28 @@ pub trait ListenerBuilderT<D>: Sized {
         self.callbacks().drained = Some(Box::new(callback));
         self
     }
+
+    /// Set the callback for the `command` event, called with the commands received by the node of the stream
+    /// that are not handled by the stream itself.
+    #[cfg(feature = "v0_3_49")]
+    fn command<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(*const spa_sys::spa_command) + 'static,
+    {
+        self.callbacks().command = Some(Box::new(callback));
+        self
+    }
+
+    /// Set the callback for the `trigger_done` event, called when the graph completed a cycle
+    /// started with `pw_stream_trigger_process()` by a driver stream.
+    #[cfg(feature = "v0_3_49")]
+    fn trigger_done<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + 'static,
+    {
+        self.callbacks().trigger_done = Some(Box::new(callback));
+        self
+    }
 }
 
 pub struct ListenerLocalBuilder<'a, D> {
@@ -635,7 +908,8 @@ impl<'a, D> ListenerLocalBuilder<'a, D> {
     /// Stop building the listener and register it on the stream. Returns a
     /// `StreamListener` handlle that will un-register the listener on drop.
     pub fn register(self) -> Result<StreamListener<D>, Error> {
-        let (events, data) = self.callbacks.into_raw();
+        let (events, mut data) = self.callbacks.into_raw();
+        data.stream = Some(self.stream.ptr);
         let (listener, data) = unsafe {
             let listener: Box<spa_sys::spa_hook> = Box::new(mem::zeroed());
             let raw_listener = Box::into_raw(listener);
@@ -731,5 +1005,13 @@ bitflags! {
         const EXCLUSIVE = pw_sys::pw_stream_flags_PW_STREAM_FLAG_EXCLUSIVE;
         const DONT_RECONNECT = pw_sys::pw_stream_flags_PW_STREAM_FLAG_DONT_RECONNECT;
         const ALLOC_BUFFERS = pw_sys::pw_stream_flags_PW_STREAM_FLAG_ALLOC_BUFFERS;
+        #[cfg(feature = "v0_3_34")]
+        const TRIGGER = pw_sys::pw_stream_flags_PW_STREAM_FLAG_TRIGGER;
+        #[cfg(feature = "v0_3_73")]
+        const ASYNC = pw_sys::pw_stream_flags_PW_STREAM_FLAG_ASYNC;
+        #[cfg(feature = "v0_3_80")]
+        const EARLY_PROCESS = pw_sys::pw_stream_flags_PW_STREAM_FLAG_EARLY_PROCESS;
+        #[cfg(feature = "v1_1")]
+        const RT_TRIGGER = pw_sys::pw_stream_flags_PW_STREAM_FLAG_RT_TRIGGER;
     }
 }
@@ -0,0 +1,152 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::{
+    cell::{Cell, UnsafeCell},
+    fmt, mem, os, ptr,
+};
+
+use spa::utils::Fraction;
+
+/// The IO areas of a stream, as received in the `io_changed` event.
+#[derive(Default)]
+pub(crate) struct IoAreas {
+    position: Cell<Option<ptr::NonNull<spa_sys::spa_io_position>>>,
+    rate_match: Cell<Option<ptr::NonNull<spa_sys::spa_io_rate_match>>>,
+}
+
+impl IoAreas {
+    /// Keep track of the area `area` of id `id` and size `size`.
+    ///
+    /// A null area means that the area was removed.
+    #[allow(non_upper_case_globals)]
+    pub(crate) fn update(&self, id: u32, area: *mut os::raw::c_void, size: u32) {
+        fn checked<T>(area: *mut os::raw::c_void, size: u32) -> Option<ptr::NonNull<T>> {
+            if (size as usize) < mem::size_of::<T>() {
+                return None;
+            }
+            ptr::NonNull::new(area as *mut T)
+        }
+
+        match id {
+            spa_sys::spa_io_type_SPA_IO_Position => self.position.set(checked(area, size)),
+            spa_sys::spa_io_type_SPA_IO_RateMatch => self.rate_match.set(checked(area, size)),
+            _ => {}
+        }
+    }
+
+    /// # Safety
+    /// The area must not have been removed since it was last updated.
+    pub(crate) unsafe fn position(&self) -> Option<&IoPosition> {
+        self.position
+            .get()
+            .map(|area| &*(area.as_ptr() as *const IoPosition))
+    }
+
+    /// # Safety
+    /// The area must not have been removed since it was last updated.
+    pub(crate) unsafe fn rate_match(&self) -> Option<&IoRateMatch> {
+        self.rate_match
+            .get()
+            .map(|area| &*(area.as_ptr() as *const IoRateMatch))
+    }
+}
+
+/// The `SPA_IO_Position` area of a stream, describing the clock and position of the graph.
+///
+/// It is updated by the driver of the graph at the start of each cycle, so it is read through a shared reference,
+/// like a [`Cell`], and each getter returns the value of the current cycle.
+#[repr(transparent)]
+pub struct IoPosition(UnsafeCell<spa_sys::spa_io_position>);
+
+impl IoPosition {
+    /// Get a raw pointer to the `spa_io_position` struct, e.g. to access the segments.
+    pub fn as_ptr(&self) -> *mut spa_sys::spa_io_position {
+        self.0.get()
+    }
+
+    /// The time of the current cycle in nanoseconds, in the clock's time base.
+    pub fn nsec(&self) -> u64 {
+        unsafe { (*self.as_ptr()).clock.nsec }
+    }
+
+    /// The rate of the clock, the duration of one sample in seconds.
+    pub fn rate(&self) -> Fraction {
+        let rate = unsafe { (*self.as_ptr()).clock.rate };
+        Fraction {
+            num: rate.num,
+            denom: rate.denom,
+        }
+    }
+
+    /// The position of the current cycle, in samples.
+    pub fn position(&self) -> u64 {
+        unsafe { (*self.as_ptr()).clock.position }
+    }
+
+    /// The duration of the current cycle, in samples.
+    pub fn duration(&self) -> u64 {
+        unsafe { (*self.as_ptr()).clock.duration }
+    }
+
+    /// The rate difference between the clock and the monotonic time.
+    pub fn rate_diff(&self) -> f64 {
+        unsafe { (*self.as_ptr()).clock.rate_diff }
+    }
+}
+
+impl fmt::Debug for IoPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IoPosition")
+            .field("nsec", &self.nsec())
+            .field("rate", &self.rate())
+            .field("position", &self.position())
+            .field("duration", &self.duration())
+            .field("rate_diff", &self.rate_diff())
+            .finish()
+    }
+}
+
+/// The `SPA_IO_RateMatch` area of a stream, used to adjust the rate of a resampler.
+///
+/// This area is shared with the resampler of the stream, so it is modified through a shared reference,
+/// like a [`Cell`].
+#[repr(transparent)]
+pub struct IoRateMatch(UnsafeCell<spa_sys::spa_io_rate_match>);
+
+impl IoRateMatch {
+    /// Get a raw pointer to the `spa_io_rate_match` struct.
+    pub fn as_ptr(&self) -> *mut spa_sys::spa_io_rate_match {
+        self.0.get()
+    }
+
+    /// The extra delay introduced by the resampler, in samples.
+    pub fn delay(&self) -> u32 {
+        unsafe { (*self.as_ptr()).delay }
+    }
+
+    /// The requested number of samples for the next cycle.
+    pub fn size(&self) -> u32 {
+        unsafe { (*self.as_ptr()).size }
+    }
+
+    /// The rate adjustment of the resampler, `1.0` meaning no adjustment.
+    pub fn rate(&self) -> f64 {
+        unsafe { (*self.as_ptr()).rate }
+    }
+
+    /// Set the rate adjustment of the resampler.
+    pub fn set_rate(&self, rate: f64) {
+        unsafe { (*self.as_ptr()).rate = rate }
+    }
+}
+
+impl fmt::Debug for IoRateMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IoRateMatch")
+            .field("delay", &self.delay())
+            .field("size", &self.size())
+            .field("rate", &self.rate())
+            .finish()
+    }
+}
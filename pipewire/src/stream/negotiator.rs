@@ -0,0 +1,155 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::{ptr, rc::Rc};
+
+use spa::pod::{deserialize::PodDeserializer, Object, Value};
+
+use super::{ParamArena, Stream, StreamFlags};
+use crate::error::Error;
+
+/// A helper handling format negotiation of a [`Stream`].
+///
+/// The negotiator is created with the list of formats supported by the application,
+/// which are offered as `EnumFormat` params when connecting the stream using [`connect`](`Self::connect`).
+///
+/// Once the stream has been attached to a listener using [`negotiate`](`super::ListenerBuilderT::negotiate`),
+/// the negotiator takes care of the `param_changed` event:
+/// When the server settles on one of the offered formats, the callback set with
+/// [`format_chosen`](`Self::format_chosen`) is called with the chosen format,
+/// and the params set with [`params`](`Self::params`), such as the `Buffers` param, are sent to the server.
+///
+/// The negotiator can be cheaply cloned, so that the same instance can be used to both register the listener and
+/// connect the stream.
+///
+/// # Examples
+/// ```no_run
+/// use pipewire::prelude::*;
+/// use pipewire::{properties, spa, stream::{Negotiator, Stream, StreamFlags}};
+/// use spa::pod::{Object, Value};
+///
+/// # fn formats() -> Vec<Value> { unimplemented!() }
+/// let mainloop = pipewire::MainLoop::new()?;
+///
+/// // The `EnumFormat` objects supported by the application.
+/// let negotiator = Negotiator::new(formats()).format_chosen(|format: &Object| {
+///     println!("Negotiated format: {:?}", format);
+/// });
+///
+/// let stream = Stream::<()>::simple(
+///     &mainloop,
+///     "negotiation-example",
+///     properties! {
+///         *pipewire::keys::MEDIA_TYPE => "Video",
+///         *pipewire::keys::MEDIA_CATEGORY => "Capture",
+///     },
+/// )
+/// .negotiate(negotiator.clone())
+/// .create()?;
+///
+/// negotiator.connect(
+///     &stream,
+///     spa::Direction::Input,
+///     None,
+///     StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+/// )?;
+///
+/// mainloop.run();
+/// # Ok::<(), pipewire::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct Negotiator {
+    formats: Rc<Vec<Value>>,
+    params: Rc<Vec<Value>>,
+    #[allow(clippy::type_complexity)]
+    format_chosen: Option<Rc<dyn Fn(&Object)>>,
+}
+
+impl Negotiator {
+    /// Create a new negotiator offering the provided formats.
+    ///
+    /// Each format should be an `Object` of type `Format`, usually containing `Choice`s
+    /// for the properties that can be picked by the server.
+    /// Formats are offered in order of preference.
+    pub fn new(formats: Vec<Value>) -> Self {
+        Self {
+            formats: Rc::new(formats),
+            params: Rc::new(Vec::new()),
+            format_chosen: None,
+        }
+    }
+
+    /// Set the params to be sent to the server once a format has been chosen, such as the `Buffers` param.
+    #[must_use]
+    pub fn params(mut self, params: Vec<Value>) -> Self {
+        self.params = Rc::new(params);
+        self
+    }
+
+    /// Set the callback to be called with the format once one has been chosen.
+    #[must_use]
+    pub fn format_chosen<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Object) + 'static,
+    {
+        self.format_chosen = Some(Rc::new(callback));
+        self
+    }
+
+    /// Connect the stream, offering the formats of the negotiator as `EnumFormat` params.
+    ///
+    /// See [`Stream::connect`] for details about the parameters.
+    ///
+    /// Returns [`Error::SerializationFailed`] if one of the formats can not be serialized.
+    pub fn connect<D>(
+        &self,
+        stream: &Stream<D>,
+        direction: spa::Direction,
+        id: Option<u32>,
+        flags: StreamFlags,
+    ) -> Result<(), Error> {
+        let formats = ParamArena::from_values(&self.formats)?;
+
+        stream.connect(direction, id, flags, &mut formats.pods())
+    }
+
+    /// Handle the `param_changed` event of `stream`.
+    ///
+    /// This is called automatically when the negotiator is registered using
+    /// [`negotiate`](`super::ListenerBuilderT::negotiate`),
+    /// but can also be called manually from a `param_changed` callback.
+    ///
+    /// Params other than `Format` are ignored,
+    /// as are formats that are cleared by the server or that can not be deserialized.
+    ///
+    /// Returns [`Error::SerializationFailed`] if one of the params can not be serialized,
+    /// in which case the stream is put into the error state when called automatically.
+    pub fn handle_param_changed<D>(
+        &self,
+        stream: &Stream<D>,
+        id: u32,
+        param: *const spa_sys::spa_pod,
+    ) -> Result<(), Error> {
+        if id != spa_sys::spa_param_type_SPA_PARAM_Format {
+            return Ok(());
+        }
+
+        let format = match ptr::NonNull::new(param as *mut spa_sys::spa_pod)
+            .and_then(|param| unsafe { PodDeserializer::deserialize_ptr::<Value>(param).ok() })
+        {
+            Some(Value::Object(format)) => format,
+            _ => return Ok(()),
+        };
+
+        if let Some(ref cb) = self.format_chosen {
+            cb(&format);
+        }
+
+        if !self.params.is_empty() {
+            let params = ParamArena::from_values(&self.params)?;
+            stream.update_params(&mut params.pods())?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::io::Cursor;
+
+use spa::pod::{
+    serialize::{PodSerializeError, PodSerializer},
+    Value,
+};
+
+/// Storage for serialized params, keeping them alive while pointers to them are in use.
+///
+/// Methods such as [`Stream::connect`](`super::Stream::connect`) and
+/// [`Stream::update_params`](`super::Stream::update_params`) take raw pointers to pods,
+/// which must stay valid during the call.
+/// The arena owns the serialized pods, so the pointers returned by [`pods`](`Self::pods`)
+/// are valid for as long as the arena is alive and not modified.
+///
+/// # Examples
+/// ```no_run
+/// use pipewire::stream::{ParamArena, Stream, StreamFlags};
+/// use pipewire::spa::pod::Value;
+///
+/// # fn connect(stream: &Stream<()>, format: Value) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut params = ParamArena::new();
+/// params.push(&format)?;
+///
+/// stream.connect(
+///     pipewire::spa::Direction::Input,
+///     None,
+///     StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+///     &mut params.pods(),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ParamArena {
+    buffers: Vec<Vec<u8>>,
+}
+
+impl ParamArena {
+    /// Create a new, empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new arena holding the provided values, in order.
+    pub fn from_values(values: &[Value]) -> Result<Self, PodSerializeError> {
+        let mut arena = Self::new();
+        for value in values {
+            arena.push(value)?;
+        }
+        Ok(arena)
+    }
+
+    /// Serialize `value` into the arena.
+    pub fn push(&mut self, value: &Value) -> Result<(), PodSerializeError> {
+        let (cursor, _) = PodSerializer::serialize(Cursor::new(Vec::new()), value)?;
+        self.buffers.push(cursor.into_inner());
+        Ok(())
+    }
+
+    /// Get the number of pods in the arena.
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns `true` if the arena does not contain any pod.
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Get pointers to the pods in the arena.
+    ///
+    /// The pointers are only valid as long as the arena is alive and not modified.
+    pub fn pods(&self) -> Vec<*const spa_sys::spa_pod> {
+        self.buffers
+            .iter()
+            .map(|buffer| buffer.as_ptr() as *const spa_sys::spa_pod)
+            .collect()
+    }
+}
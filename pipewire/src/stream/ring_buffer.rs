@@ -0,0 +1,292 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+use std::{
+    cell::UnsafeCell,
+    convert::TryInto,
+    fmt, ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A lock-free single producer, single consumer ring buffer of bytes.
+///
+/// The ring buffer is meant to move audio data between the realtime `process` callback of a stream and the
+/// non-realtime code of the application:
+/// The buffer is allocated once when creating it, after which reading and writing never allocate, lock or block.
+///
+/// The buffer is split into a [`RingBufferWriter`] and a [`RingBufferReader`] on creation,
+/// which can be sent to different threads.
+///
+/// Reads and writes are not required to be a multiple of the frame size,
+/// but the reader and the writer should both stick to whole frames to keep the samples aligned.
+///
+/// # Examples
+/// ```rust
+/// use pipewire::stream::RingBuffer;
+/// use std::time::Duration;
+///
+/// // Buffer 100ms of stereo 48kHz audio with 32 bits float samples.
+/// let (mut writer, mut reader) = RingBuffer::for_audio(48000, 2, 4, Duration::from_millis(100));
+/// assert_eq!(writer.capacity(), 48000 / 10 * 2 * 4);
+///
+/// // In the `process` callback of a capture stream:
+/// assert_eq!(writer.write(&[1, 2, 3, 4, 5, 6, 7, 8]), 8);
+///
+/// // In the application thread:
+/// let mut samples = [0; 8];
+/// assert_eq!(reader.read(&mut samples), 8);
+/// assert_eq!(samples, [1, 2, 3, 4, 5, 6, 7, 8]);
+/// ```
+pub struct RingBuffer {
+    buffer: Box<[UnsafeCell<u8>]>,
+    /// Position of the reader, in `0..2 * capacity`, so that a full buffer can be told apart from an empty one.
+    read: AtomicUsize,
+    /// Position of the writer, in `0..2 * capacity`.
+    write: AtomicUsize,
+}
+
+// Safety: The reader and the writer only ever access the parts of the buffer that the other one does not access,
+// as synchronized by the `read` and `write` positions.
+unsafe impl Sync for RingBuffer {}
+
+impl RingBuffer {
+    /// Create a new ring buffer able to hold `capacity` bytes.
+    ///
+    /// # Panics
+    /// If `capacity` is zero or larger than `usize::MAX / 2`.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(capacity: usize) -> (RingBufferWriter, RingBufferReader) {
+        assert!(capacity > 0, "Ring buffer capacity must not be zero");
+        assert!(capacity <= usize::MAX / 2, "Ring buffer capacity too large");
+
+        let shared = Arc::new(Self {
+            buffer: (0..capacity).map(|_| UnsafeCell::new(0)).collect(),
+            read: AtomicUsize::new(0),
+            write: AtomicUsize::new(0),
+        });
+
+        (
+            RingBufferWriter {
+                shared: shared.clone(),
+            },
+            RingBufferReader { shared },
+        )
+    }
+
+    /// Create a new ring buffer able to hold `duration` of audio.
+    ///
+    /// The capacity is a whole number of frames of `channels` samples of `sample_size` bytes each,
+    /// played at `rate` frames per second.
+    ///
+    /// # Panics
+    /// If the resulting capacity is zero or does not fit in memory.
+    pub fn for_audio(
+        rate: u32,
+        channels: u32,
+        sample_size: u32,
+        duration: Duration,
+    ) -> (RingBufferWriter, RingBufferReader) {
+        let frames = u128::from(rate) * duration.as_nanos() / 1_000_000_000;
+        let capacity = frames * u128::from(channels) * u128::from(sample_size);
+
+        Self::new(capacity.try_into().expect("Ring buffer capacity too large"))
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Number of bytes between the `read` and `write` positions.
+    fn filled(&self, read: usize, write: usize) -> usize {
+        let capacity = self.capacity();
+        (write + 2 * capacity - read) % (2 * capacity)
+    }
+
+    fn advance(&self, pos: usize, len: usize) -> usize {
+        (pos + len) % (2 * self.capacity())
+    }
+
+    /// Split the `len` bytes starting at position `pos` into at most two contiguous ranges of the buffer.
+    fn ranges(&self, pos: usize, len: usize) -> (usize, usize, usize) {
+        let start = pos % self.capacity();
+        let first = len.min(self.capacity() - start);
+
+        (start, first, len - first)
+    }
+
+    fn as_mut_ptr(&self) -> *mut u8 {
+        // `UnsafeCell<u8>` has the same memory layout as `u8`.
+        self.buffer.as_ptr() as *mut u8
+    }
+}
+
+impl fmt::Debug for RingBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("capacity", &self.capacity())
+            .field("read", &self.read)
+            .field("write", &self.write)
+            .finish()
+    }
+}
+
+/// The writing half of a [`RingBuffer`].
+#[derive(Debug)]
+pub struct RingBufferWriter {
+    shared: Arc<RingBuffer>,
+}
+
+impl RingBufferWriter {
+    /// The total number of bytes the ring buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// The number of bytes that can currently be written.
+    pub fn free(&self) -> usize {
+        let read = self.shared.read.load(Ordering::Acquire);
+        let write = self.shared.write.load(Ordering::Relaxed);
+
+        self.capacity() - self.shared.filled(read, write)
+    }
+
+    /// Write as many bytes of `data` as there is room for in the buffer.
+    ///
+    /// Returns the number of bytes written, which is less than the length of `data` if the buffer is full.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        let len = data.len().min(self.free());
+        let write = self.shared.write.load(Ordering::Relaxed);
+        let (start, first, second) = self.shared.ranges(write, len);
+
+        // Safety: The `len` bytes after the write position are not accessed by the reader
+        // until the write position has been updated below.
+        unsafe {
+            let buffer = self.shared.as_mut_ptr();
+            ptr::copy_nonoverlapping(data.as_ptr(), buffer.add(start), first);
+            ptr::copy_nonoverlapping(data.as_ptr().add(first), buffer, second);
+        }
+
+        self.shared
+            .write
+            .store(self.shared.advance(write, len), Ordering::Release);
+
+        len
+    }
+}
+
+/// The reading half of a [`RingBuffer`].
+#[derive(Debug)]
+pub struct RingBufferReader {
+    shared: Arc<RingBuffer>,
+}
+
+impl RingBufferReader {
+    /// The total number of bytes the ring buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity()
+    }
+
+    /// The number of bytes that can currently be read.
+    pub fn available(&self) -> usize {
+        let read = self.shared.read.load(Ordering::Relaxed);
+        let write = self.shared.write.load(Ordering::Acquire);
+
+        self.shared.filled(read, write)
+    }
+
+    /// Read as many bytes as available into `buf`.
+    ///
+    /// Returns the number of bytes read, which is less than the length of `buf` if not enough data is available.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.available());
+        let read = self.shared.read.load(Ordering::Relaxed);
+        let (start, first, second) = self.shared.ranges(read, len);
+
+        // Safety: The `len` bytes after the read position have been written and are not accessed by the writer
+        // until the read position has been updated below.
+        unsafe {
+            let buffer = self.shared.as_mut_ptr();
+            ptr::copy_nonoverlapping(buffer.add(start), buf.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(buffer, buf.as_mut_ptr().add(first), second);
+        }
+
+        self.shared
+            .read
+            .store(self.shared.advance(read, len), Ordering::Release);
+
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn wrap_around() {
+        let (mut writer, mut reader) = RingBuffer::new(4);
+        let mut buf = [0; 4];
+
+        assert_eq!(writer.write(&[1, 2, 3]), 3);
+        assert_eq!(reader.read(&mut buf[..2]), 2);
+        assert_eq!(buf[..2], [1, 2]);
+
+        // Only 3 bytes are free, and the write wraps around the end of the buffer.
+        assert_eq!(writer.free(), 3);
+        assert_eq!(writer.write(&[4, 5, 6, 7]), 3);
+        assert_eq!(writer.free(), 0);
+        assert_eq!(writer.write(&[8]), 0);
+
+        assert_eq!(reader.available(), 4);
+        assert_eq!(reader.read(&mut buf), 4);
+        assert_eq!(buf, [3, 4, 5, 6]);
+        assert_eq!(reader.read(&mut buf), 0);
+    }
+
+    #[test]
+    fn for_audio() {
+        let (writer, reader) = RingBuffer::for_audio(44100, 2, 2, Duration::from_millis(20));
+        assert_eq!(writer.capacity(), 882 * 2 * 2);
+        assert_eq!(reader.capacity(), writer.capacity());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        let _ = RingBuffer::new(0);
+    }
+
+    #[test]
+    fn threads() {
+        const LEN: usize = 10_000;
+        let (mut writer, mut reader) = RingBuffer::new(7);
+
+        let producer = thread::spawn(move || {
+            let data: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+            let mut written = 0;
+            while written < LEN {
+                match writer.write(&data[written..(written + 5).min(LEN)]) {
+                    0 => thread::yield_now(),
+                    len => written += len,
+                }
+            }
+        });
+
+        let mut received = Vec::with_capacity(LEN);
+        let mut buf = [0; 3];
+        while received.len() < LEN {
+            match reader.read(&mut buf) {
+                0 => thread::yield_now(),
+                len => received.extend_from_slice(&buf[..len]),
+            }
+        }
+        producer.join().unwrap();
+
+        assert!(received.iter().enumerate().all(|(i, b)| *b == i as u8));
+    }
+}
@@ -3,9 +3,17 @@ use std::fmt;
 // Macro generating the ObjectType enum
 macro_rules! object_type {
     ($( ($x:ident, $version:ident) ),*) => {
+        /// The type of a pipewire object, as announced by the registry.
+        ///
+        /// This covers all the standard interfaces of pipewire and its extensions,
+        /// and is used to bind objects or create them with [`Core::create_object`](`crate::Core::create_object`).
         #[derive(Debug, PartialEq, Clone)]
         pub enum ObjectType {
-            $($x,)*
+            $(
+                #[doc = concat!("`PipeWire:Interface:", stringify!($x), "`")]
+                $x,
+            )*
+            /// An interface not known to the bindings, holding its full type string.
             Other(String),
         }
 
@@ -19,6 +27,7 @@ macro_rules! object_type {
                 }
             }
 
+            /// Get the full type string of the interface, e.g. `"PipeWire:Interface:Node"`.
             pub fn to_str(&self) -> &str {
                 match self {
                     $(
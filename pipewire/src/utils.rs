@@ -1,8 +1,113 @@
 // Copyright The pipewire-rs Contributors.
 // SPDX-License-Identifier: MIT
 
-use std::thread;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    thread,
+};
 
 pub fn assert_main_thread() {
     assert_eq!(thread::current().name(), Some("main"));
 }
+
+thread_local! {
+    /// The payload of a panic caught by [`catch_panic`], waiting to be resumed by [`resume_panic`].
+    static PANIC: RefCell<Option<Box<dyn Any + Send>>> = RefCell::new(None);
+    /// The main loops currently running on this thread, the innermost one last.
+    static RUNNING_MAIN_LOOPS: RefCell<Vec<*mut pw_sys::pw_main_loop>> = RefCell::new(Vec::new());
+    /// The number of calls into libpipewire made by [`resuming_panic`] currently in progress on this thread.
+    static RESUMING_CALLS: Cell<usize> = Cell::new(0);
+}
+
+/// Call `f` from a callback called by libpipewire, catching any panic.
+///
+/// Unwinding into C code is undefined behavior, so the panic is stored instead,
+/// and the innermost main loop running on this thread is asked to quit so that
+/// [`MainLoop::run`](`crate::MainLoopInner::run`) can resume the panic once back in Rust code.
+///
+/// If `f` panics, `R::default()` is returned to libpipewire.
+///
+/// See [`catch_panic_or`] for panics that can not be resumed.
+pub(crate) fn catch_panic<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+    R: Default,
+{
+    catch_panic_or(f, || ())
+}
+
+/// Variant of [`catch_panic`] calling `unresumable` if the panic can not be resumed.
+///
+/// A panic can only be resumed if the callback is called while a main loop is running on this thread,
+/// or synchronously from a call into libpipewire wrapped in [`resuming_panic`].
+/// Otherwise, such as for a callback called from the data loop thread, the panic is logged and dropped,
+/// and `unresumable` can stop the callback from being called again.
+pub(crate) fn catch_panic_or<F, R>(f: F, unresumable: impl FnOnce()) -> R
+where
+    F: FnOnce() -> R,
+    R: Default,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(res) => res,
+        Err(payload) => {
+            let resumable = RESUMING_CALLS.with(|calls| calls.get() > 0)
+                || RUNNING_MAIN_LOOPS.with(|loops| !loops.borrow().is_empty());
+
+            if resumable {
+                PANIC.with(|panic| {
+                    // Only keep the first panic, the other ones may just be consequences of it.
+                    panic.borrow_mut().get_or_insert(payload);
+                });
+                RUNNING_MAIN_LOOPS.with(|loops| {
+                    if let Some(main_loop) = loops.borrow().last() {
+                        unsafe { pw_sys::pw_main_loop_quit(*main_loop) };
+                    }
+                });
+            } else {
+                eprintln!(
+                    "pipewire: dropping panic in a callback that can not be resumed: {}",
+                    panic_message(&*payload)
+                );
+                unresumable();
+            }
+
+            R::default()
+        }
+    }
+}
+
+/// Call `f`, which calls into libpipewire, resuming the panics of the callbacks it calls synchronously.
+pub(crate) fn resuming_panic<R>(f: impl FnOnce() -> R) -> R {
+    RESUMING_CALLS.with(|calls| calls.set(calls.get() + 1));
+    let res = f();
+    RESUMING_CALLS.with(|calls| calls.set(calls.get() - 1));
+
+    resume_panic();
+    res
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/// Call `run`, which runs the main loop `main_loop`, so that panics caught while it runs make it quit.
+pub(crate) fn running_main_loop(main_loop: *mut pw_sys::pw_main_loop, run: impl FnOnce()) {
+    RUNNING_MAIN_LOOPS.with(|loops| loops.borrow_mut().push(main_loop));
+    run();
+    RUNNING_MAIN_LOOPS.with(|loops| loops.borrow_mut().pop());
+}
+
+/// Resume the panic caught by [`catch_panic`], if any.
+pub(crate) fn resume_panic() {
+    if let Some(payload) = PANIC.with(|panic| panic.borrow_mut().take()) {
+        panic::resume_unwind(payload);
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright The pipewire-rs Contributors.
+// SPDX-License-Identifier: MIT
+
+//! Test of the selection of the remote when connecting a context.
+//!
+//! This test modifies the environment of the process, so it lives in its own test binary
+//! holding a single test, to not race with other tests calling into libpipewire.
+
+use std::{
+    fs, io,
+    os::unix::net::UnixListener,
+    path::{Path, PathBuf},
+};
+
+use pipewire::{keys, properties, Context, MainLoop};
+
+/// Points the remote selection to a temporary directory, restoring the environment when dropped.
+struct RemoteEnv {
+    dir: PathBuf,
+}
+
+impl RemoteEnv {
+    fn new(remote: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("pipewire-rs-remote-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        std::env::set_var("PIPEWIRE_RUNTIME_DIR", &dir);
+        std::env::set_var("PIPEWIRE_REMOTE", remote);
+
+        Self { dir }
+    }
+
+    fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for RemoteEnv {
+    fn drop(&mut self) {
+        std::env::remove_var("PIPEWIRE_RUNTIME_DIR");
+        std::env::remove_var("PIPEWIRE_REMOTE");
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[test]
+fn connect_remote_name_overrides_env() {
+    let env = RemoteEnv::new("env-remote");
+
+    let env_remote = UnixListener::bind(env.dir().join("env-remote")).unwrap();
+    let props_remote = UnixListener::bind(env.dir().join("props-remote")).unwrap();
+    env_remote.set_nonblocking(true).unwrap();
+    props_remote.set_nonblocking(true).unwrap();
+
+    let mainloop = MainLoop::new().unwrap();
+    let context = Context::new(&mainloop).unwrap();
+
+    // The property takes precedence over the environment.
+    let _core = context
+        .connect(Some(properties! {
+            *keys::REMOTE_NAME => "props-remote",
+        }))
+        .unwrap();
+    assert!(props_remote.accept().is_ok());
+    assert_eq!(
+        env_remote.accept().unwrap_err().kind(),
+        io::ErrorKind::WouldBlock
+    );
+
+    // Without the property, the environment is used.
+    let _core = context.connect(None).unwrap();
+    assert!(env_remote.accept().is_ok());
+}